@@ -1,86 +1,64 @@
 //! Application actions for worktree management
 
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
+
 use tracing::{debug, error, info};
 
-use super::App;
-use super::state::{CreateWorktreeState, ViewMode};
-use crate::git::WorktreeAgent;
+use super::state::{
+    CreateWorktreeState, DeleteConfirmState, DeleteTarget, DetailPanel, LoadedDetails,
+    TrashViewState, ViewMode,
+};
+use super::{App, STATUS_REFRESH_INTERVAL};
+use crate::git::{WorktreeAgent, branch_details, short_hash, stash_counts, worktree_status};
+use crate::trash::{Trash, TrashEntry};
 use crate::ui::BranchStatus;
-use crate::watcher::WatcherEvent;
+use crate::watcher::{self, WatcherEvent};
+
+/// How many recent commits the branch details show
+const DETAIL_COMMITS: usize = 10;
 
 impl App {
     /// Update the branch list from current state
     pub(super) fn update_branch_list(&mut self) {
         let worktree_agent = WorktreeAgent::new(&self.repo);
         let worktrees = worktree_agent.list().unwrap_or_default();
+        // Only worth showing where a branch comes from if there is a choice
+        let show_remote = self.config.remote_names().len() > 1;
 
-        let mut items: Vec<crate::ui::BranchItem> = self
+        let items: Vec<crate::ui::BranchItem> = self
             .watcher
             .get_known_branches()
             .iter()
             // Filter out ignored branches - they don't appear in the list
-            .filter(|branch| !self.config.should_ignore_branch(&branch.name))
+            .filter(|branch| !self.config.should_ignore(branch))
             .map(|branch| {
                 let existing_worktree = worktrees
                     .iter()
                     .find(|w| w.branch.as_deref() == Some(&branch.name));
 
-                // Check queue/processing status first
-                let status = if self.watcher.is_current(&branch.name) {
-                    // Currently being processed - check if hook is running
-                    if self.watcher.has_running_hook(&branch.name) {
-                        BranchStatus::RunningHook
-                    } else {
-                        BranchStatus::Creating
-                    }
-                } else if self.watcher.is_pending(&branch.name) {
-                    BranchStatus::Queued
-                } else if let Some(wt) = existing_worktree {
-                    // Check if hook is running for this worktree
-                    if self.watcher.has_running_hook(&branch.name) {
-                        BranchStatus::RunningHook
-                    } else if wt.is_prunable {
-                        BranchStatus::LocalPrunable
-                    } else {
-                        BranchStatus::LocalActive
-                    }
-                } else {
-                    BranchStatus::Remote
-                };
+                let status = self.watcher.branch_status(&branch.name, existing_worktree);
 
-                let is_default = self
-                    .config
-                    .base_branch
-                    .as_ref()
-                    .map(|b| b == &branch.name)
-                    .unwrap_or(false);
+                let is_default = self.config.is_base_branch(branch);
 
                 crate::ui::BranchItem {
                     name: branch.name.clone(),
+                    remote: (show_remote && !branch.is_local).then(|| branch.remote.clone()),
                     status,
                     is_default,
+                    worktree_status: existing_worktree
+                        .filter(|w| !w.is_prunable)
+                        .and_then(|_| self.worktree_status.get(&branch.name).cloned()),
+                    stale: self.watcher.stale_reason(&branch.name),
+                    orphaned: self.watcher.orphaned_since(&branch.name).is_some(),
+                    commit_time: branch.commit_time,
+                    author: branch.author.clone(),
                 }
             })
             .collect();
 
-        // Sort: active worktrees first, then by name
-        items.sort_by(|a, b| {
-            let a_active = matches!(
-                a.status,
-                BranchStatus::LocalActive | BranchStatus::LocalPrunable
-            );
-            let b_active = matches!(
-                b.status,
-                BranchStatus::LocalActive | BranchStatus::LocalPrunable
-            );
-
-            match (a_active, b_active) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.name.cmp(&b.name),
-            }
-        });
-
         self.branch_list_state.update_items(items);
     }
 
@@ -97,51 +75,213 @@ impl App {
         // Start non-blocking fetch
         self.watcher.start_fetch(
             self.repo.root().to_path_buf(),
-            self.config.remote_name.clone(),
+            self.config.remote_names(),
+            self.config.poll_mode,
             self.event_tx.clone(),
         );
 
         self.update_status();
     }
 
+    /// Start computing the status of every worktree on a background thread
+    ///
+    /// Running `git status` in many worktrees takes a while, so the result is
+    /// picked up by `process_worktree_status` once it is ready.
+    pub(super) fn refresh_worktree_status(&mut self) {
+        // Don't start another refresh while one is in progress
+        if self.worktree_status_rx.is_some() {
+            return;
+        }
+        self.last_status_refresh = Instant::now();
+
+        let worktrees: Vec<(String, std::path::PathBuf)> = WorktreeAgent::new(&self.repo)
+            .list()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|w| !w.is_prunable)
+            .filter_map(|w| Some((w.branch?, w.path)))
+            .collect();
+        let main_root = self.repo.main_root().to_path_buf();
+
+        let (tx, rx) = mpsc::channel();
+        self.worktree_status_rx = Some(rx);
+
+        thread::spawn(move || {
+            let mut stashes = stash_counts(&main_root);
+            let statuses: HashMap<_, _> = worktrees
+                .into_iter()
+                .filter_map(|(branch, path)| {
+                    let mut status = worktree_status(&path).ok()?;
+                    status.stashes = stashes.remove(&branch).unwrap_or(0);
+                    Some((branch, status))
+                })
+                .collect();
+            let _ = tx.send(statuses);
+        });
+    }
+
+    /// Pick up the result of a background worktree status refresh
+    pub(super) fn process_worktree_status(&mut self) {
+        let Some(rx) = &self.worktree_status_rx else {
+            return;
+        };
+
+        match rx.try_recv() {
+            Ok(statuses) => {
+                self.worktree_status_rx = None;
+                if statuses != self.worktree_status {
+                    self.worktree_status = statuses;
+                    self.update_branch_list();
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.worktree_status_rx = None,
+        }
+    }
+
+    /// Cycle through the sort modes of the branch list
+    pub(super) fn cycle_sort_mode(&mut self) {
+        self.config.sort_mode = self.config.sort_mode.next();
+        self.branch_list_state.set_sort_mode(self.config.sort_mode);
+        if let Err(e) = self.config.save(self.repo.main_root()) {
+            error!("Failed to save config: {}", e);
+        }
+    }
+
+    /// Switch the branch list between flat and grouped by prefix
+    pub(super) fn toggle_grouping(&mut self) {
+        self.config.group_by_prefix = !self.config.group_by_prefix;
+        self.branch_list_state
+            .set_grouped(self.config.group_by_prefix);
+        if let Err(e) = self.config.save(self.repo.main_root()) {
+            error!("Failed to save config: {}", e);
+        }
+    }
+
+    /// Switch the right panel between the command log and the branch details
+    pub(super) fn toggle_detail_panel(&mut self) {
+        self.detail_panel = match self.detail_panel {
+            DetailPanel::Log => DetailPanel::Details,
+            DetailPanel::Details => DetailPanel::Log,
+        };
+        self.branch_logs_state.scroll = 0;
+    }
+
+    /// Load the details of the selected branch in the background, unless they are
+    /// already loaded (or loading) for its current commit
+    pub(super) fn load_branch_details(&mut self) {
+        if self.branch_details_rx.is_some() {
+            return;
+        }
+        let Some(branch) = self.branch_list_state.selected_branch() else {
+            return;
+        };
+        let Some(known) = self.watcher.get_branch_by_name(&branch) else {
+            return;
+        };
+        if self
+            .branch_details
+            .as_ref()
+            .is_some_and(|d| d.branch == branch && d.commit == known.commit)
+        {
+            return;
+        }
+
+        // A checked out branch may have moved on locally, so show the local one
+        let has_worktree = WorktreeAgent::new(&self.repo)
+            .has_worktree_for_branch(&branch)
+            .unwrap_or(false);
+        let upstream = self.config.upstream(known);
+        let rev = if known.is_local || has_worktree {
+            format!("refs/heads/{}", branch)
+        } else {
+            upstream.tracking_ref()
+        };
+
+        let base_ref = watcher::base_ref(&self.repo, &self.config, &upstream);
+
+        let commit = known.commit.clone();
+        let repo_root = self.repo.root().to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        self.branch_details_rx = Some(rx);
+
+        thread::spawn(move || {
+            let result = branch_details(&repo_root, &rev, base_ref.as_deref(), DETAIL_COMMITS);
+            let _ = tx.send(LoadedDetails {
+                branch,
+                commit,
+                result,
+            });
+        });
+    }
+
+    /// Pick up branch details loaded in the background
+    pub(super) fn process_branch_details(&mut self) {
+        let Some(rx) = &self.branch_details_rx else {
+            return;
+        };
+
+        match rx.try_recv() {
+            Ok(details) => {
+                self.branch_details_rx = None;
+                self.branch_details = Some(details);
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.branch_details_rx = None,
+        }
+    }
+
+    /// Refresh the worktree status as soon as possible (after it may have changed)
+    pub(super) fn schedule_status_refresh(&mut self) {
+        self.last_status_refresh = Instant::now() - STATUS_REFRESH_INTERVAL;
+    }
+
     /// Process pending watcher events
     pub(super) fn process_watcher_events(&mut self) {
         // Always check for hook output
         self.watcher.check_running_hooks(&self.event_tx);
 
         while let Ok(event) = self.event_rx.try_recv() {
+            // Let the watcher advance its pipeline first (branch list, queue)
+            self.watcher
+                .handle_event(&event, &self.repo, &self.config, &self.event_tx);
+
+            if let Some(control) = self.control.as_mut() {
+                control.broadcast(&event);
+            }
+
             match event {
-                WatcherEvent::FetchStarted => {
+                WatcherEvent::FetchStarted(_) => {
                     self.status.is_fetching = true;
                 }
-                WatcherEvent::FetchCompleted(output) => {
-                    // Log fetch result to command logs
-                    if let Some(msg) = output {
-                        self.watcher.add_fetch_log(&self.config.remote_name, &msg);
-                    } else {
-                        // No warnings/errors - log simple success
-                        self.watcher.add_fetch_success_log(&self.config.remote_name);
-                    }
-                    // Process the completed fetch - update branches
-                    self.watcher
-                        .on_fetch_complete(&self.repo, &mut self.config, &self.event_tx);
-                    self.status.is_fetching = false;
-                    self.status.last_fetch = self.config.last_fetch;
+                WatcherEvent::FetchCompleted(..) => {
+                    self.status.is_fetching = self.watcher.is_fetching();
+                    self.status.last_fetch = self.watcher.last_fetch();
                     self.status.last_error = None;
                     self.update_branch_list();
                     self.update_status();
+                    self.schedule_status_refresh();
                 }
-                WatcherEvent::FetchFailed(msg) => {
-                    self.watcher.on_fetch_failed();
-                    self.watcher
-                        .add_fetch_log(&self.config.remote_name, &format!("Error: {}", msg));
-                    self.status.is_fetching = false;
-                    self.status.last_error = Some(msg);
+                WatcherEvent::FetchFailed(remote, msg) => {
+                    self.status.is_fetching = self.watcher.is_fetching();
+                    self.status.last_error = Some(format!("{}: {}", remote, msg));
                 }
                 WatcherEvent::NewBranchesFound(branches) => {
                     info!("New branches found: {:?}", branches);
                     self.update_branch_list();
                 }
+                WatcherEvent::BranchUpdated { branch, old, new } => {
+                    let (old, new) = (short_hash(&old), short_hash(&new));
+                    info!("Branch updated: {} {} → {}", branch, old, new);
+                    self.status.last_notice = Some(format!("{} updated {}→{}", branch, old, new));
+                    self.update_branch_list();
+                }
+                WatcherEvent::BranchDeleted(branch) => {
+                    info!("Branch deleted on remote: {}", branch);
+                    self.status.last_notice = Some(format!("{} deleted on remote", branch));
+                    self.update_branch_list();
+                    self.update_status();
+                }
                 WatcherEvent::WorktreeCreating(branch) => {
                     // Update UI to show creating status
                     if let Some(item) = self
@@ -157,23 +297,25 @@ impl App {
                     info!("Worktree created for: {}", branch);
                     self.update_branch_list();
                     self.update_status();
-
-                    // If no hook is configured, process next pending branch
-                    if self.config.post_create_command.is_none() {
-                        self.watcher
-                            .try_process_next(&self.repo, &mut self.config, &self.event_tx);
-                    }
+                    self.schedule_status_refresh();
                 }
                 WatcherEvent::WorktreeCreateFailed(branch, msg) => {
                     error!("Worktree creation failed for {}: {}", branch, msg);
                     self.status.last_error = Some(format!("{}: {}", branch, msg));
                     self.update_branch_list();
-
-                    // Process next pending branch even if this one failed
-                    self.watcher
-                        .try_process_next(&self.repo, &mut self.config, &self.event_tx);
                 }
-                WatcherEvent::HookStarted(branch) => {
+                WatcherEvent::WorktreeRemoved(branch, _path) => {
+                    info!("Worktree removed for: {}", branch);
+                    self.update_branch_list();
+                    self.update_status();
+                    self.schedule_status_refresh();
+                }
+                WatcherEvent::WorktreeRemoveFailed(branch, msg) => {
+                    error!("Worktree removal failed for {}: {}", branch, msg);
+                    self.status.last_error = Some(format!("{}: {}", branch, msg));
+                    self.update_branch_list();
+                }
+                WatcherEvent::HookStarted(branch, _stage) => {
                     self.status.running_hooks += 1;
                     if let Some(item) = self
                         .branch_list_state
@@ -183,25 +325,25 @@ impl App {
                     {
                         item.status = BranchStatus::RunningHook;
                     }
+                    // The branch may now pass the running hooks filter
+                    self.branch_list_state.refilter();
                 }
                 WatcherEvent::HookOutput(_, _) => {
                     // Output is already captured in watcher
                 }
-                WatcherEvent::HookCompleted(branch, exit_code) => {
+                WatcherEvent::HookCompleted(branch, stage, exit_code) => {
                     self.status.running_hooks = self.status.running_hooks.saturating_sub(1);
 
                     if exit_code != 0 {
                         self.status.last_error = Some(format!(
-                            "Hook failed for {}: exit code {}",
-                            branch, exit_code
+                            "{} hook failed for {}: exit code {}",
+                            stage, branch, exit_code
                         ));
                     }
 
                     self.update_branch_list();
-
-                    // Process next pending branch (sequential worktree creation)
-                    self.watcher
-                        .try_process_next(&self.repo, &mut self.config, &self.event_tx);
+                    // Hooks often change files in the worktree
+                    self.schedule_status_refresh();
                 }
             }
         }
@@ -219,33 +361,58 @@ impl App {
 
     /// Create worktree for the selected branch (queued for sequential processing)
     pub(super) fn create_selected_worktree(&mut self) {
+        if self.branch_list_state.has_marks() {
+            self.create_marked_worktrees();
+            return;
+        }
+
         let Some(selected) = self.branch_list_state.selected().cloned() else {
             return;
         };
 
-        // Don't create if already has worktree or already queued/processing
-        if matches!(
-            selected.status,
-            BranchStatus::LocalActive
-                | BranchStatus::Creating
-                | BranchStatus::RunningHook
-                | BranchStatus::Queued
-        ) {
+        if !can_queue(&selected.status) {
             return;
         }
 
         // Queue the branch for sequential processing
         self.watcher
-            .queue_branch(&self.repo, &mut self.config, &selected.name, &self.event_tx);
+            .queue_branch(&self.repo, &self.config, &selected.name, &self.event_tx);
 
         self.update_branch_list();
-        if let Err(e) = self.config.save(self.repo.main_root()) {
-            error!("Failed to save config: {}", e);
+    }
+
+    /// Queue worktree creation for all marked branches
+    fn create_marked_worktrees(&mut self) {
+        let branches: Vec<String> = self
+            .branch_list_state
+            .marked_items()
+            .into_iter()
+            .filter(|item| can_queue(&item.status))
+            .map(|item| item.name.clone())
+            .collect();
+        self.branch_list_state.clear_marks();
+
+        if branches.is_empty() {
+            self.status.last_error = Some("All marked branches have a worktree".to_string());
+            return;
+        }
+
+        for branch in &branches {
+            self.watcher
+                .queue_branch(&self.repo, &self.config, branch, &self.event_tx);
         }
+        self.status.last_notice = Some(format!("Queued {} worktrees", branches.len()));
+
+        self.update_branch_list();
     }
 
     /// Delete worktree for the selected branch
     pub(super) fn delete_selected_worktree(&mut self) {
+        if self.branch_list_state.has_marks() {
+            self.delete_marked_worktrees();
+            return;
+        }
+
         let Some(selected) = self.branch_list_state.selected().cloned() else {
             return;
         };
@@ -258,49 +425,180 @@ impl App {
             return;
         }
 
+        self.open_delete_confirm(vec![selected.name]);
+    }
+
+    /// Ask once to delete the worktrees of all marked branches
+    ///
+    /// Marked branches without a worktree and the main worktree are skipped.
+    fn delete_marked_worktrees(&mut self) {
+        let branches: Vec<String> = self
+            .branch_list_state
+            .marked_items()
+            .into_iter()
+            .filter(|item| {
+                matches!(
+                    item.status,
+                    BranchStatus::LocalActive | BranchStatus::LocalPrunable
+                )
+            })
+            .map(|item| item.name.clone())
+            .collect();
+
+        self.open_delete_confirm(branches);
+    }
+
+    /// Show the delete dialog for the worktrees of `branches`, with what removing
+    /// each one would lose
+    fn open_delete_confirm(&mut self, branches: Vec<String>) {
         let worktree_agent = WorktreeAgent::new(&self.repo);
+        let worktrees = worktree_agent.list().unwrap_or_default();
 
-        // Check if this is the main worktree (the one with .git)
-        if let Ok(worktrees) = worktree_agent.list()
-            && let Some(wt) = worktrees
+        let mut targets = Vec::new();
+        for branch in branches {
+            let Some(wt) = worktrees
                 .iter()
-                .find(|w| w.branch.as_deref() == Some(&selected.name))
-            && wt.is_main
-        {
-            self.status.last_error = Some("Cannot delete the main worktree".to_string());
+                .find(|w| w.branch.as_deref() == Some(branch.as_str()))
+            else {
+                continue;
+            };
+            // The main worktree (the one with .git) can't be removed
+            if wt.is_main {
+                continue;
+            }
+
+            // A missing directory has no changes left to lose
+            let status = if wt.path.exists() {
+                worktree_status(&wt.path).ok()
+            } else {
+                Some(Default::default())
+            };
+            let unpushed = worktree_agent.unpushed_commits(&wt.head).unwrap_or(0);
+            let upstream = self.watcher.upstream(&self.repo, &self.config, &branch);
+            let base_ref = watcher::base_ref(&self.repo, &self.config, &upstream);
+            let merged = base_ref
+                .as_ref()
+                .is_some_and(|base_ref| self.repo.is_merged(&wt.head, base_ref));
+
+            targets.push(DeleteTarget {
+                branch,
+                path: wt.path.clone(),
+                status,
+                unpushed,
+                base: base_ref.map(|r| r.trim_start_matches("refs/remotes/").to_string()),
+                merged,
+            });
+        }
+
+        if targets.is_empty() {
+            let message = if self.branch_list_state.has_marks() {
+                "No marked branch has a removable worktree"
+            } else {
+                "Cannot delete the main worktree"
+            };
+            self.status.last_error = Some(message.to_string());
             return;
         }
 
-        // Show confirmation dialog
-        self.view_mode = ViewMode::DeleteConfirm {
-            branch: selected.name.clone(),
-            input: String::new(),
-        };
+        self.view_mode =
+            ViewMode::DeleteConfirm(DeleteConfirmState::new(targets, self.config.trash));
     }
 
     /// Actually perform the worktree deletion after confirmation
-    pub(super) fn do_delete_worktree(&mut self, branch: &str) {
-        let worktree_agent = WorktreeAgent::new(&self.repo);
-
-        // Get worktree path
-        if let Ok(Some(path)) = worktree_agent.get_worktree_path(branch)
-            && let Err(e) = worktree_agent.remove(&path, false)
-        {
-            error!("Failed to remove worktree: {}", e);
-            self.status.last_error = Some(e.to_string());
+    ///
+    /// Runs the pre-remove hooks first if configured; the outcome arrives as
+    /// watcher events. Dirty worktrees are kept unless forced.
+    pub(super) fn do_delete_worktrees(&mut self, state: &DeleteConfirmState) {
+        let mut kept = 0;
+        for target in &state.targets {
+            let Some(options) = state.options_for(target) else {
+                kept += 1;
+                continue;
+            };
+            self.watcher.start_remove(
+                &self.repo,
+                &self.config,
+                &target.branch,
+                &target.path,
+                options,
+                &self.event_tx,
+            );
         }
 
+        if kept > 0 {
+            self.status.last_notice = Some(format!(
+                "Kept {} worktree{} with uncommitted changes",
+                kept,
+                if kept == 1 { "" } else { "s" }
+            ));
+        }
+        self.branch_list_state.clear_marks();
         self.update_branch_list();
     }
 
-    /// Untrack (ignore) the selected branch - removes it from the list
-    pub(super) fn untrack_selected(&mut self) {
+    /// Open the trash view
+    pub(super) fn open_trash(&mut self) {
+        self.view_mode = ViewMode::Trash(TrashViewState {
+            entries: Trash::new(&self.repo).list(),
+            selected: 0,
+        });
+    }
+
+    /// Move a worktree back from the trash
+    pub(super) fn restore_from_trash(&mut self, entry: &TrashEntry) {
+        match Trash::new(&self.repo).restore(&self.repo, entry) {
+            Ok(log_messages) => {
+                self.watcher
+                    .add_worktree_log(&entry.branch, "restore", &log_messages);
+                self.status.last_notice = Some(format!("Restored {}", entry.branch));
+                self.view_mode = ViewMode::Main;
+                self.update_branch_list();
+                self.branch_list_state.select_by_name(&entry.branch);
+                self.schedule_status_refresh();
+            }
+            Err(e) => {
+                self.status.last_error = Some(format!("Failed to restore: {}", e));
+            }
+        }
+    }
+
+    /// Cancel the hook running for the selected branch
+    pub(super) fn cancel_selected_hook(&mut self) {
         let Some(selected) = self.branch_list_state.selected().cloned() else {
             return;
         };
 
+        if !self.watcher.cancel_hook(&selected.name) {
+            self.status.last_error = Some("No hook running for this branch".to_string());
+        }
+    }
+
+    /// Untrack (ignore) the selected branch, or all marked ones - removes them from the list
+    pub(super) fn untrack_selected(&mut self) {
+        let branches: Vec<String> = if self.branch_list_state.has_marks() {
+            self.branch_list_state
+                .marked_items()
+                .into_iter()
+                .map(|item| item.name.clone())
+                .collect()
+        } else {
+            self.branch_list_state
+                .selected_branch()
+                .into_iter()
+                .collect()
+        };
+        if branches.is_empty() {
+            return;
+        }
+        self.branch_list_state.clear_marks();
+
         // Add to ignore list
-        self.config.ignore_branch(&selected.name);
+        for branch in &branches {
+            self.config.ignore_branch(branch);
+        }
+        if branches.len() > 1 {
+            self.status.last_notice = Some(format!("Untracked {} branches", branches.len()));
+        }
 
         if let Err(e) = self.config.save(self.repo.main_root()) {
             error!("Failed to save config: {}", e);
@@ -380,13 +678,6 @@ impl App {
 
     /// Create a new worktree with a new branch
     pub(super) fn do_create_new_worktree(&mut self, new_branch: &str, base_branch: &str) {
-        let worktree_agent = WorktreeAgent::new(&self.repo);
-
-        // Get the worktree path using the same logic as existing worktrees
-        let worktree_path = self
-            .config
-            .get_worktree_path(self.repo.main_root(), new_branch);
-
         // Determine the full ref for the base branch
         // If it's a remote branch, use origin/branch, otherwise use just the branch name
         let base_ref = if let Some(branch_info) = self.watcher.get_branch_by_name(base_branch) {
@@ -397,13 +688,10 @@ impl App {
             }
         } else {
             // Default to remote ref if not found
-            format!("{}/{}", self.config.remote_name, base_branch)
+            self.config.default_upstream(base_branch).tracking_ref()
         };
 
-        info!(
-            "Creating new worktree: {} from {} at {:?}",
-            new_branch, base_ref, worktree_path
-        );
+        info!("Creating new worktree: {} from {}", new_branch, base_ref);
 
         // Add to command logs
         self.watcher.add_command_log(
@@ -411,39 +699,31 @@ impl App {
             &format!("Creating new branch '{}' from '{}'", new_branch, base_ref),
         );
 
-        match worktree_agent.create_new_branch(new_branch, &base_ref, &worktree_path) {
-            Ok(log_messages) => {
-                for msg in log_messages {
-                    self.watcher.add_command_log(new_branch, &msg);
-                }
-
-                // Add the new branch to known branches so it shows up immediately
-                self.watcher.add_local_branch(new_branch);
+        // Queued like any other worktree, so `max_concurrent_jobs` holds; failures
+        // are reported as watcher events
+        self.watcher.queue_new_branch(
+            &self.repo,
+            &self.config,
+            new_branch,
+            &base_ref,
+            &self.event_tx,
+        );
 
-                // Update the branch list first, then select the new branch
-                self.update_branch_list();
-                self.branch_list_state.select_by_name(new_branch);
-                self.update_status();
-
-                // Run post-create command if configured
-                if let Some(ref command) = self.config.post_create_command {
-                    self.watcher.add_command_log(
-                        new_branch,
-                        &format!("Running post-create hook: {}", command),
-                    );
-                    self.watcher.start_hook(
-                        new_branch.to_string(),
-                        command.clone(),
-                        worktree_path,
-                        self.event_tx.clone(),
-                    );
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to create worktree: {}", e);
-                self.watcher.add_command_log(new_branch, &error_msg);
-                self.status.last_error = Some(error_msg);
-            }
-        }
+        // Update the branch list first, then select the new branch
+        self.update_branch_list();
+        self.branch_list_state.select_by_name(new_branch);
+        self.update_status();
     }
 }
+
+/// Whether a worktree can be queued for a branch: it has none and isn't queued
+/// or being processed already
+fn can_queue(status: &BranchStatus) -> bool {
+    !matches!(
+        status,
+        BranchStatus::LocalActive
+            | BranchStatus::Creating
+            | BranchStatus::RunningHook
+            | BranchStatus::Queued
+    )
+}
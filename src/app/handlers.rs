@@ -4,6 +4,7 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKi
 
 use super::App;
 use super::state::{CreateWorktreeStep, ViewMode};
+use crate::ui::BranchFilter;
 
 impl App {
     /// Handle key events
@@ -15,7 +16,9 @@ impl App {
             ViewMode::Error(_) => self.handle_error_keys(key),
             ViewMode::Setup => self.handle_setup_keys(key),
             ViewMode::Settings => self.handle_settings_keys(key),
-            ViewMode::DeleteConfirm { .. } => self.handle_delete_confirm_keys(key),
+            ViewMode::DeleteConfirm(_) => self.handle_delete_confirm_keys(key),
+            ViewMode::Trash(_) => self.handle_trash_keys(key),
+            ViewMode::IgnoreList(_) => self.handle_ignore_list_keys(key),
             ViewMode::CreateWorktree(_) => self.handle_create_worktree_keys(key),
         }
     }
@@ -50,11 +53,39 @@ impl App {
 
     /// Handle main view keys
     pub(super) fn handle_main_keys(&mut self, key: KeyEvent) {
+        if self.branch_list_state.filter_editing {
+            self.handle_filter_keys(key);
+            return;
+        }
+
         match (key.modifiers, key.code) {
+            // Esc drops the marks first, then the filters
+            (_, KeyCode::Esc) if self.branch_list_state.has_marks() => {
+                self.branch_list_state.clear_marks();
+            }
+            (_, KeyCode::Esc) if self.branch_list_state.is_filtered() => {
+                self.branch_list_state.status_filter = BranchFilter::All;
+                self.branch_list_state.set_filter(String::new());
+            }
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => {
                 self.running = false;
             }
+            (_, KeyCode::Char('/')) => {
+                self.branch_list_state.filter_editing = true;
+            }
+            (_, KeyCode::Char('1')) => {
+                self.branch_list_state
+                    .toggle_status_filter(BranchFilter::Local);
+            }
+            (_, KeyCode::Char('2')) => {
+                self.branch_list_state
+                    .toggle_status_filter(BranchFilter::RemoteOnly);
+            }
+            (_, KeyCode::Char('3')) => {
+                self.branch_list_state
+                    .toggle_status_filter(BranchFilter::RunningHooks);
+            }
             (_, KeyCode::Char('?')) => {
                 self.view_mode = ViewMode::Help;
             }
@@ -88,15 +119,45 @@ impl App {
                     self.branch_logs_state.scroll_up();
                 }
             }
+            // Enter on a group header folds it
+            (_, KeyCode::Enter) if self.branch_list_state.toggle_selected_group() => {}
             (_, KeyCode::Enter) => {
                 self.create_selected_worktree();
             }
+            (_, KeyCode::Left) => {
+                self.branch_list_state.set_group_collapsed(true);
+            }
+            (_, KeyCode::Right) => {
+                self.branch_list_state.set_group_collapsed(false);
+            }
+            (_, KeyCode::Char('S')) => {
+                self.cycle_sort_mode();
+            }
+            (_, KeyCode::Char('T')) => {
+                self.toggle_grouping();
+            }
+            (_, KeyCode::Char(' ')) => {
+                self.branch_list_state.toggle_mark();
+                self.branch_list_state.select_next();
+            }
+            (_, KeyCode::Char('v')) => {
+                self.branch_list_state.toggle_visual();
+            }
             (_, KeyCode::Char('d')) => {
                 self.delete_selected_worktree();
             }
             (_, KeyCode::Char('u')) => {
                 self.untrack_selected();
             }
+            (_, KeyCode::Char('x')) => {
+                self.cancel_selected_hook();
+            }
+            (_, KeyCode::Char('z')) => {
+                self.open_trash();
+            }
+            (_, KeyCode::Char('i')) => {
+                self.open_ignore_list();
+            }
             (_, KeyCode::Char('r')) => {
                 self.do_poll();
             }
@@ -112,10 +173,45 @@ impl App {
             (_, KeyCode::Char('o')) => {
                 self.open_selected_worktree();
             }
+            (_, KeyCode::Tab) => {
+                self.toggle_detail_panel();
+            }
             _ => {}
         }
     }
 
+    /// Handle keys while the branch filter is being typed
+    fn handle_filter_keys(&mut self, key: KeyEvent) {
+        let state = &mut self.branch_list_state;
+        match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => {
+                self.running = false;
+                return;
+            }
+            (_, KeyCode::Esc) => {
+                state.filter_editing = false;
+                state.set_filter(String::new());
+            }
+            (_, KeyCode::Enter) => {
+                state.filter_editing = false;
+            }
+            (_, KeyCode::Backspace) => {
+                let mut filter = state.filter.clone();
+                filter.pop();
+                state.set_filter(filter);
+            }
+            // Other Ctrl/Alt chords don't type anything
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                let filter = format!("{}{}", state.filter, c);
+                state.set_filter(filter);
+            }
+            (_, KeyCode::Down) => state.select_next(),
+            (_, KeyCode::Up) => state.select_previous(),
+            _ => return,
+        }
+        self.branch_logs_state.scroll = 0; // Reset scroll on selection change
+    }
+
     /// Handle logs view keys
     pub(super) fn handle_logs_keys(&mut self, key: KeyEvent) {
         match key.code {
@@ -161,30 +257,53 @@ impl App {
 
     /// Handle keys in delete confirmation dialog
     pub(super) fn handle_delete_confirm_keys(&mut self, key: KeyEvent) {
-        // Extract branch and input from view mode
-        let (branch, mut input) = match &self.view_mode {
-            ViewMode::DeleteConfirm { branch, input } => (branch.clone(), input.clone()),
+        let mut state = match &self.view_mode {
+            ViewMode::DeleteConfirm(state) => state.clone(),
             _ => return,
         };
 
         match key.code {
             KeyCode::Esc => {
                 self.view_mode = ViewMode::Main;
+                return;
             }
-            KeyCode::Enter => {
-                if input.to_lowercase() == "yes" {
-                    self.view_mode = ViewMode::Main;
-                    self.do_delete_worktree(&branch);
-                }
-                // If not "yes", do nothing - user must type exactly "yes"
+            // If not "yes", do nothing - user must type exactly "yes"
+            KeyCode::Enter if state.input.to_lowercase() == "yes" => {
+                self.view_mode = ViewMode::Main;
+                self.do_delete_worktrees(&state);
+                return;
             }
+            KeyCode::Char('f') => state.force = !state.force,
+            KeyCode::Char('b') => state.delete_branch = !state.delete_branch,
             KeyCode::Backspace => {
-                input.pop();
-                self.view_mode = ViewMode::DeleteConfirm { branch, input };
+                state.input.pop();
             }
-            KeyCode::Char(c) => {
-                input.push(c);
-                self.view_mode = ViewMode::DeleteConfirm { branch, input };
+            KeyCode::Char(c) => state.input.push(c),
+            _ => {}
+        }
+        self.view_mode = ViewMode::DeleteConfirm(state);
+    }
+
+    /// Handle keys in the trash view
+    pub(super) fn handle_trash_keys(&mut self, key: KeyEvent) {
+        let ViewMode::Trash(state) = &mut self.view_mode else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('z') => {
+                self.view_mode = ViewMode::Main;
+            }
+            KeyCode::Down | KeyCode::Char('j') if state.selected + 1 < state.entries.len() => {
+                state.selected += 1;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                state.selected = state.selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                if let Some(entry) = state.entries.get(state.selected).cloned() {
+                    self.restore_from_trash(&entry);
+                }
             }
             _ => {}
         }
@@ -229,10 +348,8 @@ impl App {
                     }
 
                     // Enter or Tab moves to next step
-                    KeyCode::Enter | KeyCode::Tab => {
-                        if state.next_step() {
-                            self.view_mode = ViewMode::CreateWorktree(state);
-                        }
+                    KeyCode::Enter | KeyCode::Tab if state.next_step() => {
+                        self.view_mode = ViewMode::CreateWorktree(state);
                     }
 
                     // Typing filters the list
@@ -0,0 +1,580 @@
+//! Ignore list manager: edit the ignore patterns with a live preview of the
+//! branches they hide, and restore ignored branches
+//!
+//! The include and author filters are shown too, read-only, so every hidden
+//! branch says what hides it.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use tracing::error;
+
+use super::App;
+use super::state::{IgnoreListState, ViewMode};
+use crate::config::{ConfigLayer, pattern_error};
+
+/// Settings besides `ignore_patterns` that hide branches, shown read-only
+const OTHER_FILTERS: [&str; 3] = ["include_patterns", "include_authors", "ignore_authors"];
+
+impl App {
+    /// Open the ignore list manager
+    pub(super) fn open_ignore_list(&mut self) {
+        let branches = self
+            .watcher
+            .get_known_branches()
+            .into_iter()
+            .cloned()
+            .collect();
+        self.view_mode = ViewMode::IgnoreList(IgnoreListState::new(branches, &self.config));
+    }
+
+    /// The patterns of a filter setting
+    fn filter_patterns(&self, filter: &str) -> &[String] {
+        match filter {
+            "include_patterns" => &self.config.include_patterns,
+            "include_authors" => &self.config.include_authors,
+            "ignore_authors" => &self.config.ignore_authors,
+            _ => &self.config.ignore_patterns,
+        }
+    }
+
+    /// Handle keys in the ignore list manager
+    pub(super) fn handle_ignore_list_keys(&mut self, key: KeyEvent) {
+        let ViewMode::IgnoreList(state) = &mut self.view_mode else {
+            return;
+        };
+
+        if let Some(editor) = state.editor.as_mut() {
+            match key.code {
+                KeyCode::Esc => {
+                    state.editor = None;
+                    state.error = None;
+                }
+                KeyCode::Enter => self.apply_pattern_edit(),
+                KeyCode::Backspace => {
+                    editor.input.pop();
+                    state.error = None;
+                    state.update_preview();
+                }
+                KeyCode::Char(c) => {
+                    editor.input.push(c);
+                    state.error = None;
+                    state.update_preview();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let patterns = &self.config.ignore_patterns;
+        let rows = patterns.len() + state.hidden.len();
+        let on_pattern = state.selected < patterns.len();
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => {
+                self.view_mode = ViewMode::Main;
+            }
+            KeyCode::Down | KeyCode::Char('j') if state.selected + 1 < rows => {
+                state.selected += 1;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                state.selected = state.selected.saturating_sub(1);
+            }
+            KeyCode::Char('a') => state.edit(None, String::new()),
+            KeyCode::Enter | KeyCode::Char('e') if on_pattern => {
+                state.edit(Some(state.selected), patterns[state.selected].clone());
+            }
+            KeyCode::Char('d') | KeyCode::Delete if on_pattern => {
+                let pattern = self.config.ignore_patterns.remove(state.selected);
+                self.status.last_notice = Some(format!("Removed ignore pattern {}", pattern));
+                self.save_ignore_patterns();
+            }
+            KeyCode::Enter | KeyCode::Char('r') if !on_pattern => {
+                let Some(&index) = state.hidden.get(state.selected - patterns.len()) else {
+                    return;
+                };
+                let branch = state.branches[index].name.clone();
+                let filter = state.filtered_by[index];
+                if !state.patterns.matches(&branch) {
+                    // Only the config file changes the other filters
+                    self.status.last_notice = Some(format!(
+                        "{} is hidden by {}, change it in the config",
+                        branch,
+                        filter.unwrap_or_default()
+                    ));
+                    return;
+                }
+
+                self.config.unignore_branch(&branch);
+                self.status.last_notice = Some(match filter {
+                    Some(filter) => format!(
+                        "Removed {} from the ignore list, {} still hides it",
+                        branch, filter
+                    ),
+                    None => format!("Restored {}", branch),
+                });
+                self.save_ignore_patterns();
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply the pattern being typed, unless it is invalid
+    fn apply_pattern_edit(&mut self) {
+        let ViewMode::IgnoreList(state) = &mut self.view_mode else {
+            return;
+        };
+        let Some(editor) = state.editor.take() else {
+            return;
+        };
+
+        let pattern = editor.input.trim().to_string();
+        let refused = if pattern.is_empty() || pattern == "!" {
+            Some("The pattern is empty".to_string())
+        } else if let Some(e) = pattern_error(&pattern) {
+            Some(format!("Invalid pattern: {}", e))
+        } else if self
+            .config
+            .ignore_patterns
+            .iter()
+            .enumerate()
+            .any(|(i, p)| *p == pattern && Some(i) != editor.index)
+        {
+            Some(format!("{} is already in the list", pattern))
+        } else {
+            None
+        };
+        if let Some(reason) = refused {
+            state.error = Some(reason);
+            state.editor = Some(editor);
+            return;
+        }
+
+        let patterns = &mut self.config.ignore_patterns;
+        state.selected = match editor.index {
+            Some(i) if i < patterns.len() => {
+                patterns[i] = pattern;
+                i
+            }
+            _ => {
+                patterns.push(pattern);
+                patterns.len() - 1
+            }
+        };
+        self.save_ignore_patterns();
+    }
+
+    /// Save a change to the ignore patterns and show its effect
+    fn save_ignore_patterns(&mut self) {
+        self.config.compile_patterns();
+        if let ViewMode::IgnoreList(state) = &mut self.view_mode {
+            // Saving puts the whole list in the local config
+            state.source = ConfigLayer::Local;
+            state.error = None;
+            state.refresh(&self.config);
+            let rows = self.config.ignore_patterns.len() + state.hidden.len();
+            state.selected = state.selected.min(rows.saturating_sub(1));
+        }
+
+        if let Err(e) = self.config.save(self.repo.main_root()) {
+            error!("Failed to save config: {}", e);
+            self.status.last_error = Some(format!("Failed to save config: {}", e));
+        }
+
+        self.update_branch_list();
+    }
+
+    /// Render the ignore list manager
+    pub(super) fn render_ignore_list(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &IgnoreListState,
+    ) {
+        // Fill background
+        frame.render_widget(
+            Block::default().style(Style::default().bg(self.theme.bg)),
+            area,
+        );
+
+        let editor_height = if state.editor.is_some() { 3 } else { 0 };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints([
+                Constraint::Length(3),             // Title
+                Constraint::Min(6),                // Patterns | preview
+                Constraint::Length(editor_height), // Pattern being typed
+                Constraint::Length(2),             // Navigation
+            ])
+            .split(area);
+
+        // Title
+        let mut title = vec![
+            Span::styled(
+                "⊘ Ignore List",
+                Style::default()
+                    .fg(self.theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "  {} of {} branches hidden",
+                    state.hidden.len(),
+                    state.branches.len()
+                ),
+                Style::default().fg(self.theme.muted),
+            ),
+        ];
+        if matches!(state.source, ConfigLayer::Global | ConfigLayer::Shared) {
+            title.push(Span::styled(
+                format!(
+                    "  (patterns from the {} config; a change copies the list to the local config, which then replaces it)",
+                    state.source.name()
+                ),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(title)).block(
+                Block::default()
+                    .borders(Borders::BOTTOM)
+                    .border_style(Style::default().fg(self.theme.muted)),
+            ),
+            chunks[0],
+        );
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+
+        self.render_ignore_rows(frame, columns[0], state);
+        self.render_ignore_preview(frame, columns[1], state);
+
+        // Pattern being typed
+        if let Some(editor) = &state.editor {
+            let title = if editor.index.is_some() {
+                " Edit pattern "
+            } else {
+                " Add pattern (glob, regex:<expr>, !<pattern> to keep branches) "
+            };
+            let mut input = vec![
+                Span::styled(&editor.input, Style::default().fg(self.theme.fg)),
+                Span::styled("█", Style::default().fg(self.theme.primary)),
+            ];
+            if let Some(error) = &state.error {
+                input.push(Span::styled(
+                    format!("  {}", error),
+                    Style::default().fg(self.theme.error),
+                ));
+            }
+            frame.render_widget(
+                Paragraph::new(Line::from(input)).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(self.theme.secondary))
+                        .title(Span::styled(
+                            title,
+                            Style::default().fg(self.theme.secondary),
+                        )),
+                ),
+                chunks[2],
+            );
+        }
+
+        // Navigation hint
+        let keys: &[(&str, &str)] = if state.editor.is_some() {
+            &[("Enter ", "apply "), ("Esc ", "cancel")]
+        } else {
+            &[
+                (" j/k ", "navigate "),
+                ("a ", "add "),
+                ("e ", "edit "),
+                ("d ", "delete "),
+                ("Enter ", "edit/restore "),
+                ("Esc/i ", "back"),
+            ]
+        };
+        let nav: Vec<Span> = keys
+            .iter()
+            .flat_map(|(key, action)| {
+                [
+                    Span::styled(*key, Style::default().fg(self.theme.primary)),
+                    Span::styled(*action, Style::default().fg(self.theme.muted)),
+                ]
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(Line::from(nav)), chunks[3]);
+    }
+
+    /// What hides a branch: the ignore pattern, and the other filter
+    fn hidden_by(&self, state: &IgnoreListState, index: usize) -> Vec<String> {
+        let patterns = &self.config.ignore_patterns;
+        let mut by: Vec<String> = state.deciding[index]
+            .filter(|&i| !patterns[i].starts_with('!'))
+            .map(|i| patterns[i].clone())
+            .into_iter()
+            .collect();
+        by.extend(state.filtered_by[index].map(str::to_string));
+        by
+    }
+
+    /// Render the patterns followed by the other filters and the branches they
+    /// hide
+    fn render_ignore_rows(&self, frame: &mut Frame, area: Rect, state: &IgnoreListState) {
+        let patterns = &self.config.ignore_patterns;
+        let muted = Style::default().fg(self.theme.muted);
+        let header = muted.add_modifier(Modifier::BOLD);
+        let mut selected_line = 0;
+        let mut row = |lines: &mut Vec<Line<'static>>,
+                       index: usize,
+                       text: String,
+                       style: Style,
+                       note: Span<'static>| {
+            let is_selected = index == state.selected && state.editor.is_none();
+            let (arrow, style) = if is_selected {
+                selected_line = lines.len();
+                (
+                    "▶ ",
+                    Style::default().fg(self.theme.bg).bg(self.theme.primary),
+                )
+            } else {
+                ("  ", style)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(arrow, Style::default().fg(self.theme.primary)),
+                Span::styled(text, style),
+                note,
+            ]));
+        };
+
+        let mut lines = vec![Line::from(Span::styled("Patterns", header))];
+        if patterns.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No patterns, press a to add one",
+                muted,
+            )));
+        }
+        for (i, pattern) in patterns.iter().enumerate() {
+            let decided = state.decided_by(i).count();
+            let (style, note) = if let Some(e) = state.patterns.error(i) {
+                (
+                    Style::default().fg(self.theme.error),
+                    Span::styled(
+                        format!("  invalid: {}", e),
+                        Style::default().fg(self.theme.error),
+                    ),
+                )
+            } else if pattern.starts_with('!') {
+                (
+                    Style::default().fg(self.theme.success),
+                    Span::styled(format!("  keeps {}", decided), muted),
+                )
+            } else {
+                (
+                    Style::default().fg(self.theme.fg),
+                    Span::styled(format!("  hides {}", decided), muted),
+                )
+            };
+            row(&mut lines, i, pattern.clone(), style, note);
+        }
+
+        // The other filters, which are only changed in the config file
+        let filters: Vec<&str> = OTHER_FILTERS
+            .into_iter()
+            .filter(|filter| !self.filter_patterns(filter).is_empty())
+            .collect();
+        if !filters.is_empty() {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled(
+                "Other filters (read-only)",
+                header,
+            )));
+        }
+        for filter in filters {
+            let hidden = state
+                .filtered_by
+                .iter()
+                .filter(|by| **by == Some(filter))
+                .count();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}: ", filter), muted),
+                Span::styled(
+                    self.filter_patterns(filter).join(", "),
+                    Style::default().fg(self.theme.fg),
+                ),
+                Span::styled(
+                    format!(
+                        "  hides {} ({} config)",
+                        hidden,
+                        self.config.source(filter).name()
+                    ),
+                    muted,
+                ),
+            ]));
+        }
+
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled("Ignored branches", header)));
+        if state.hidden.is_empty() {
+            lines.push(Line::from(Span::styled("  No branch is ignored", muted)));
+        }
+        for (i, &index) in state.hidden.iter().enumerate() {
+            let by = format!("  by {}", self.hidden_by(state, index).join(", "));
+            row(
+                &mut lines,
+                patterns.len() + i,
+                state.branches[index].name.clone(),
+                Style::default().fg(self.theme.secondary),
+                Span::styled(by, muted),
+            );
+        }
+
+        // Keep the selected row in view
+        let height = usize::from(area.height).max(1);
+        let scroll = selected_line.saturating_sub(height - 1);
+
+        frame.render_widget(
+            Paragraph::new(lines).scroll((u16::try_from(scroll).unwrap_or(u16::MAX), 0)),
+            area,
+        );
+    }
+
+    /// Render what the selected pattern or branch does, or what the pattern
+    /// being typed would change
+    fn render_ignore_preview(&self, frame: &mut Frame, area: Rect, state: &IgnoreListState) {
+        let patterns = &self.config.ignore_patterns;
+        let muted = Style::default().fg(self.theme.muted);
+        let mut lines = Vec::new();
+
+        let title = if let Some(editor) = &state.editor {
+            if let Some(e) = &editor.invalid {
+                lines.push(Line::from(Span::styled(
+                    format!("Invalid pattern: {}", e),
+                    Style::default().fg(self.theme.error),
+                )));
+            } else {
+                let hidden: Vec<usize> = editor
+                    .hidden
+                    .iter()
+                    .filter(|i| state.hidden.binary_search(i).is_err())
+                    .copied()
+                    .collect();
+                let shown: Vec<usize> = state
+                    .hidden
+                    .iter()
+                    .filter(|i| editor.hidden.binary_search(i).is_err())
+                    .copied()
+                    .collect();
+
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "Matches {} branch{}",
+                        editor.matching,
+                        if editor.matching == 1 { "" } else { "es" }
+                    ),
+                    muted,
+                )));
+                if hidden.is_empty() && shown.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        "No change to the branches shown",
+                        muted,
+                    )));
+                }
+                for index in hidden {
+                    lines.push(Line::from(Span::styled(
+                        format!("- {}  (hidden)", state.branches[index].name),
+                        Style::default().fg(self.theme.warning),
+                    )));
+                }
+                for index in shown {
+                    lines.push(Line::from(Span::styled(
+                        format!("+ {}  (shown again)", state.branches[index].name),
+                        Style::default().fg(self.theme.success),
+                    )));
+                }
+            }
+            " Preview ".to_string()
+        } else if let Some(pattern) = patterns.get(state.selected) {
+            let mut decided = state.decided_by(state.selected).peekable();
+            if let Some(e) = state.patterns.error(state.selected) {
+                lines.push(Line::from(Span::styled(
+                    format!("Invalid pattern, matches nothing: {}", e),
+                    Style::default().fg(self.theme.error),
+                )));
+            } else if decided.peek().is_none() {
+                lines.push(Line::from(Span::styled(
+                    "No current branch is decided by this pattern",
+                    muted,
+                )));
+            } else {
+                lines.push(Line::from(Span::styled(
+                    if pattern.starts_with('!') {
+                        "Keeps these branches shown:"
+                    } else {
+                        "Hides these branches:"
+                    },
+                    muted,
+                )));
+                for branch in decided {
+                    lines.push(Line::from(Span::styled(
+                        format!("  {}", branch.name),
+                        Style::default().fg(self.theme.secondary),
+                    )));
+                }
+            }
+            format!(" {} ", pattern)
+        } else if let Some(&index) = state
+            .hidden
+            .get(state.selected.saturating_sub(patterns.len()))
+        {
+            let branch = &state.branches[index].name;
+            for by in self.hidden_by(state, index) {
+                lines.push(Line::from(vec![
+                    Span::styled("Hidden by ", muted),
+                    Span::styled(by, Style::default().fg(self.theme.fg)),
+                ]));
+            }
+            lines.push(Line::raw(""));
+            let hint = match (state.patterns.matches(branch), state.filtered_by[index]) {
+                (false, Some(filter)) => {
+                    format!("Only a change to {} in the config shows it again", filter)
+                }
+                (_, filter) => {
+                    let how = if state.deciding[index].is_some_and(|i| patterns[i] == *branch) {
+                        "Enter restores it by removing that pattern".to_string()
+                    } else {
+                        format!("Enter restores it by adding !{}", branch)
+                    };
+                    match filter {
+                        Some(filter) => format!("{}, but {} still hides it", how, filter),
+                        None => how,
+                    }
+                }
+            };
+            lines.push(Line::from(Span::styled(hint, muted)));
+            format!(" {} ", branch)
+        } else {
+            " Preview ".to_string()
+        };
+
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.muted))
+                    .title(Span::styled(
+                        title,
+                        Style::default().fg(self.theme.secondary),
+                    )),
+            ),
+            area,
+        );
+    }
+}
@@ -2,11 +2,13 @@
 
 mod actions;
 mod handlers;
+mod ignore_list;
 mod settings;
 mod setup;
 mod state;
 mod views;
 
+use std::collections::HashMap;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
@@ -15,13 +17,18 @@ use crossterm::event::{self, Event, KeyEventKind};
 use ratatui::{DefaultTerminal, Frame};
 
 use crate::config::Config;
-use crate::git::Repository;
+use crate::control::{ControlContext, ControlServer};
+use crate::git::{Repository, WorktreeStatus};
+use crate::runtime::RuntimeState;
 use crate::ui::{AppStatus, BranchListState, HelpWidget, LogsState, Theme};
 use crate::watcher::{Watcher, WatcherEvent};
 
 pub use state::ViewMode;
 
-use state::{SettingsState, SetupState};
+use state::{DetailPanel, LoadedDetails, SettingsState, SetupState};
+
+/// How often the dirty/ahead/behind status of the worktrees is refreshed
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Main application state
 pub struct App {
@@ -45,10 +52,22 @@ pub struct App {
     logs_state: LogsState,
     /// Branch log state (for scrolling right panel)
     branch_logs_state: LogsState,
+    /// What the right panel shows
+    detail_panel: DetailPanel,
+    /// Details of the selected branch (last loaded)
+    branch_details: Option<LoadedDetails>,
+    /// Pending background load of branch details
+    branch_details_rx: Option<mpsc::Receiver<LoadedDetails>>,
     /// Application status
     status: AppStatus,
     /// Last poll time
     last_poll: Instant,
+    /// Local status of each worktree by branch, refreshed in the background
+    worktree_status: HashMap<String, WorktreeStatus>,
+    /// Pending background worktree status refresh
+    worktree_status_rx: Option<mpsc::Receiver<HashMap<String, WorktreeStatus>>>,
+    /// Last worktree status refresh
+    last_status_refresh: Instant,
     /// Theme
     theme: Theme,
     /// Setup wizard state (only used during first run)
@@ -57,6 +76,8 @@ pub struct App {
     settings_state: Option<SettingsState>,
     /// Directory to print after exit (for 'o' command)
     exit_to_directory: Option<std::path::PathBuf>,
+    /// Control socket for editor integrations and `gwa ctl` (if it could be bound)
+    control: Option<ControlServer>,
 }
 
 impl App {
@@ -64,10 +85,9 @@ impl App {
     pub fn new(repo_path: &std::path::Path) -> Result<Self> {
         let repo = Repository::discover(repo_path)?;
 
-        // Check if config file exists (first run detection)
+        // Check if the repository has a config file (first run detection)
         // Config is always stored in the main worktree (where .git directory is)
-        let config_path = repo.main_root().join(crate::config::CONFIG_FILE_NAME);
-        let is_first_run = !config_path.exists();
+        let is_first_run = !Config::exists_in(repo.main_root());
 
         let config = Config::load(repo.main_root())?;
 
@@ -75,20 +95,21 @@ impl App {
 
         let mut watcher = Watcher::new();
         // Only init watcher if we have a valid remote
-        if repo.remote_exists(&config.remote_name) {
-            let _ = watcher.init(&repo, &config);
+        if repo.remote_exists(&config.remote_name) && watcher.init(&repo, &config).is_ok() {
+            watcher.restore(&repo, &config, RuntimeState::load(&repo), &event_tx);
         }
 
         let status = AppStatus {
             is_fetching: false,
-            last_fetch: config.last_fetch,
+            last_fetch: watcher.last_fetch(),
             remote_branch_count: watcher.get_known_branches().len(),
             worktree_count: 0, // Will be updated on first branch list update
             running_hooks: 0,
             last_error: None,
+            last_notice: None,
             auto_create_enabled: config.auto_create_worktrees,
             poll_interval: config.poll_interval_secs,
-            remote_name: config.remote_name.clone(),
+            remote_name: config.remote_names().join(", "),
         };
 
         // Determine initial view mode and setup state
@@ -127,18 +148,31 @@ impl App {
             branch_list_state: BranchListState::new(),
             logs_state: LogsState::default(),
             branch_logs_state: LogsState::default(),
+            detail_panel: DetailPanel::default(),
+            branch_details: None,
+            branch_details_rx: None,
             status,
             last_poll: Instant::now() - Duration::from_secs(999), // Force initial poll
+            worktree_status: HashMap::new(),
+            worktree_status_rx: None,
+            last_status_refresh: Instant::now() - STATUS_REFRESH_INTERVAL, // Refresh right away
             theme: Theme::default(),
             setup_state,
             settings_state: None,
             exit_to_directory: None,
+            control: None,
         };
 
+        app.branch_list_state.sort_mode = app.config.sort_mode;
+        app.branch_list_state.grouped = app.config.group_by_prefix;
+
         // Only update branch list if not in setup mode
         if !is_first_run {
             app.update_branch_list();
         }
+        if app.view_mode == ViewMode::Main {
+            app.start_control_server();
+        }
         Ok(app)
     }
 
@@ -153,14 +187,24 @@ impl App {
         while self.running {
             // Only process events and poll if not in error or setup mode
             if !matches!(self.view_mode, ViewMode::Error(_) | ViewMode::Setup) {
-                // Process any pending watcher events
+                // Process any pending watcher events and control requests
                 self.process_watcher_events();
+                self.process_control_requests();
 
                 // Check if we need to poll
                 let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
                 if self.last_poll.elapsed() >= poll_interval {
                     self.do_poll();
                 }
+
+                self.process_worktree_status();
+                if self.detail_panel == DetailPanel::Details {
+                    self.process_branch_details();
+                    self.load_branch_details();
+                }
+                if self.last_status_refresh.elapsed() >= STATUS_REFRESH_INTERVAL {
+                    self.refresh_worktree_status();
+                }
             }
 
             // Render
@@ -180,14 +224,40 @@ impl App {
             }
         }
 
-        // Only save config if we didn't start in error mode
+        // Only save state if we didn't start in error mode
         if !started_with_error {
-            self.config.save(self.repo.main_root())?;
+            self.watcher.save_state(&self.repo);
         }
 
         Ok(self.exit_to_directory)
     }
 
+    /// Bind the control socket (a second instance simply runs without one)
+    pub(super) fn start_control_server(&mut self) {
+        match ControlServer::start(self.repo.main_root()) {
+            Ok(control) => self.control = Some(control),
+            Err(e) => tracing::warn!("Control socket disabled: {}", e),
+        }
+    }
+
+    /// Handle requests received on the control socket
+    fn process_control_requests(&mut self) {
+        let Some(control) = self.control.as_mut() else {
+            return;
+        };
+
+        let handled = control.process(&mut ControlContext {
+            watcher: &mut self.watcher,
+            repo: &self.repo,
+            config: &mut self.config,
+            event_tx: &self.event_tx,
+        });
+
+        if handled > 0 {
+            self.update_branch_list();
+        }
+    }
+
     /// Render the application
     fn render(&mut self, frame: &mut Frame) {
         let area = frame.area();
@@ -202,17 +272,25 @@ impl App {
             ViewMode::Error(msg) => self.render_error(frame, area, msg.clone()),
             ViewMode::Setup => self.render_setup(frame, area),
             ViewMode::Settings => self.render_settings(frame, area),
-            ViewMode::DeleteConfirm { branch, input } => {
-                let branch = branch.clone();
-                let input = input.clone();
+            ViewMode::DeleteConfirm(state) => {
+                let state = state.clone();
                 self.render_main(frame, area);
-                self.render_delete_confirm(frame, area, branch, input);
+                self.render_delete_confirm(frame, area, &state);
             }
             ViewMode::CreateWorktree(state) => {
                 let state = state.clone();
                 self.render_main(frame, area);
                 self.render_create_worktree(frame, area, &state);
             }
+            ViewMode::Trash(state) => {
+                let state = state.clone();
+                self.render_main(frame, area);
+                self.render_trash(frame, area, &state);
+            }
+            ViewMode::IgnoreList(state) => {
+                let state = state.clone();
+                self.render_ignore_list(frame, area, &state);
+            }
         }
     }
 }
@@ -11,6 +11,10 @@ use ratatui::{
 
 use super::App;
 use super::state::{SettingsField, SettingsState, ViewMode};
+use crate::config::HookStage;
+
+/// Upper bound for the concurrent jobs setting in the settings screen
+const MAX_CONCURRENT_JOBS: usize = 16;
 
 impl App {
     /// Open settings screen
@@ -92,7 +96,7 @@ impl App {
                                 .unwrap_or(0);
                             let next_idx = (current_idx + 1) % remotes.len();
                             self.config.remote_name = remotes[next_idx].clone();
-                            self.status.remote_name = self.config.remote_name.clone();
+                            self.status.remote_name = self.config.remote_names().join(", ");
                             // Reload branches for new remote
                             if let Ok(branches) =
                                 self.repo.get_remote_branches(&self.config.remote_name)
@@ -136,30 +140,52 @@ impl App {
                     }
                     SettingsField::PostCreateCommand => {
                         settings.editing = true;
-                        settings.edit_value =
-                            self.config.post_create_command.clone().unwrap_or_default();
+                        settings.edit_value = self
+                            .config
+                            .hook_command(HookStage::PostCreate)
+                            .unwrap_or_default()
+                            .to_string();
+                    }
+                    SettingsField::ConcurrentJobs => {
+                        settings.editing = true;
+                        settings.edit_value = self.config.max_concurrent_jobs.to_string();
                     }
                 }
             }
-            KeyCode::Left => {
-                // Decrease numeric values
+            // Decrease numeric values
+            KeyCode::Left
                 if settings.selected_field == SettingsField::PollInterval
-                    && self.config.poll_interval_secs > 5
-                {
-                    self.config.poll_interval_secs -= 5;
-                    self.status.poll_interval = self.config.poll_interval_secs;
-                    let _ = self.config.save(self.repo.main_root());
-                }
+                    && self.config.poll_interval_secs > 5 =>
+            {
+                self.config.poll_interval_secs -= 5;
+                self.status.poll_interval = self.config.poll_interval_secs;
+                let _ = self.config.save(self.repo.main_root());
             }
-            KeyCode::Right => {
-                // Increase numeric values
+            // Increase numeric values
+            KeyCode::Right
                 if settings.selected_field == SettingsField::PollInterval
-                    && self.config.poll_interval_secs < 300
-                {
-                    self.config.poll_interval_secs += 5;
-                    self.status.poll_interval = self.config.poll_interval_secs;
-                    let _ = self.config.save(self.repo.main_root());
-                }
+                    && self.config.poll_interval_secs < 300 =>
+            {
+                self.config.poll_interval_secs += 5;
+                self.status.poll_interval = self.config.poll_interval_secs;
+                let _ = self.config.save(self.repo.main_root());
+            }
+            KeyCode::Left
+                if settings.selected_field == SettingsField::ConcurrentJobs
+                    && self.config.max_concurrent_jobs > 1 =>
+            {
+                self.config.max_concurrent_jobs -= 1;
+                let _ = self.config.save(self.repo.main_root());
+            }
+            KeyCode::Right
+                if settings.selected_field == SettingsField::ConcurrentJobs
+                    && self.config.max_concurrent_jobs < MAX_CONCURRENT_JOBS =>
+            {
+                self.config.max_concurrent_jobs += 1;
+                // A freed slot can start the next queued branch right away
+                self.watcher
+                    .try_process_next(&self.repo, &self.config, &self.event_tx);
+                let _ = self.config.save(self.repo.main_root());
             }
             _ => {}
         }
@@ -180,11 +206,19 @@ impl App {
             SettingsField::WorktreeBaseDir => {
                 self.config.worktree_base_dir = settings.edit_value.clone();
             }
+            SettingsField::ConcurrentJobs => {
+                if let Ok(val) = settings.edit_value.parse::<usize>() {
+                    self.config.max_concurrent_jobs = val.clamp(1, MAX_CONCURRENT_JOBS);
+                    self.watcher
+                        .try_process_next(&self.repo, &self.config, &self.event_tx);
+                }
+            }
             SettingsField::PostCreateCommand => {
                 if settings.edit_value.is_empty() {
-                    self.config.post_create_command = None;
+                    self.config.set_post_create_command(None);
                 } else {
-                    self.config.post_create_command = Some(settings.edit_value.clone());
+                    self.config
+                        .set_post_create_command(Some(settings.edit_value.clone()));
                 }
             }
             _ => {}
@@ -269,9 +303,9 @@ impl App {
                         format!("{}_ (editing)", settings.edit_value)
                     } else {
                         self.config
-                            .post_create_command
-                            .clone()
-                            .unwrap_or_else(|| "(none)".to_string())
+                            .hook_command(HookStage::PostCreate)
+                            .unwrap_or("(none)")
+                            .to_string()
                     },
                 ),
                 SettingsField::AutoCreate => (
@@ -282,6 +316,14 @@ impl App {
                         "No".to_string()
                     },
                 ),
+                SettingsField::ConcurrentJobs => (
+                    "Concurrent Jobs",
+                    if settings.editing && is_selected {
+                        format!("{}_ (editing)", settings.edit_value)
+                    } else {
+                        format!("{}  ← → to adjust", self.config.max_concurrent_jobs)
+                    },
+                ),
             };
 
             let line_style = if is_selected {
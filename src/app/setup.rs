@@ -445,20 +445,11 @@ impl App {
                 }
             }
             KeyCode::Up | KeyCode::Char('k') => match setup.step {
-                SetupStep::Remote => {
-                    if setup.selected_index > 0 {
-                        setup.selected_index -= 1;
-                    }
-                }
-                SetupStep::PollInterval => {
-                    if setup.poll_interval < 300 {
-                        setup.poll_interval += 5;
-                    }
+                SetupStep::Remote | SetupStep::BaseBranch if setup.selected_index > 0 => {
+                    setup.selected_index -= 1;
                 }
-                SetupStep::BaseBranch => {
-                    if setup.selected_index > 0 {
-                        setup.selected_index -= 1;
-                    }
+                SetupStep::PollInterval if setup.poll_interval < 300 => {
+                    setup.poll_interval += 5;
                 }
                 SetupStep::AutoCreate => {
                     setup.selected_index = if setup.selected_index == 0 { 1 } else { 0 };
@@ -467,15 +458,13 @@ impl App {
             },
             KeyCode::Down | KeyCode::Char('j') => {
                 match setup.step {
-                    SetupStep::Remote => {
-                        if setup.selected_index < setup.remotes.len().saturating_sub(1) {
-                            setup.selected_index += 1;
-                        }
+                    SetupStep::Remote
+                        if setup.selected_index < setup.remotes.len().saturating_sub(1) =>
+                    {
+                        setup.selected_index += 1;
                     }
-                    SetupStep::PollInterval => {
-                        if setup.poll_interval > 5 {
-                            setup.poll_interval -= 5;
-                        }
+                    SetupStep::PollInterval if setup.poll_interval > 5 => {
+                        setup.poll_interval -= 5;
                     }
                     SetupStep::BaseBranch => {
                         let max = setup.branches.len(); // +1 for "auto" option but we start at 0
@@ -629,7 +618,8 @@ impl App {
         self.config.poll_interval_secs = setup.poll_interval;
         self.config.worktree_base_dir = setup.worktree_base_dir;
         self.config.base_branch = setup.base_branch;
-        self.config.post_create_command = setup.post_create_command;
+        self.config
+            .set_post_create_command(setup.post_create_command);
         self.config.auto_create_worktrees = setup.auto_create;
 
         // Save config
@@ -639,13 +629,14 @@ impl App {
         let _ = self.watcher.init(&self.repo, &self.config);
 
         // Update status
-        self.status.remote_name = self.config.remote_name.clone();
+        self.status.remote_name = self.config.remote_names().join(", ");
         self.status.poll_interval = self.config.poll_interval_secs;
         self.status.auto_create_enabled = self.config.auto_create_worktrees;
 
         // Switch to main view
         self.view_mode = super::state::ViewMode::Main;
         self.update_branch_list();
+        self.start_control_server();
     }
 
     pub(super) fn finish_setup_with_defaults(&mut self) {
@@ -661,5 +652,6 @@ impl App {
 
         self.view_mode = super::state::ViewMode::Main;
         self.update_branch_list();
+        self.start_control_server();
     }
 }
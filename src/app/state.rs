@@ -1,5 +1,12 @@
 //! Application state types and enums
 
+use std::path::PathBuf;
+
+use crate::config::{CompiledPatterns, Config, ConfigLayer};
+use crate::git::{BranchDetails, RemoteBranch, WorktreeStatus};
+use crate::trash::TrashEntry;
+use crate::watcher::RemoveOptions;
+
 /// Current view mode
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ViewMode {
@@ -16,14 +23,245 @@ pub enum ViewMode {
     /// Settings screen
     Settings,
     /// Delete confirmation dialog
-    DeleteConfirm {
-        /// Branch name to delete
-        branch: String,
-        /// User input (must be "yes" to proceed)
-        input: String,
-    },
+    DeleteConfirm(DeleteConfirmState),
     /// Create new worktree dialog
     CreateWorktree(CreateWorktreeState),
+    /// Worktrees in the trash, to restore one
+    Trash(TrashViewState),
+    /// Ignore patterns and the branches they hide
+    IgnoreList(IgnoreListState),
+}
+
+/// What the right-hand panel of the main view shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailPanel {
+    /// Command output for the selected branch
+    #[default]
+    Log,
+    /// Recent commits and changes versus the base branch
+    Details,
+}
+
+/// Details of a branch, loaded in the background
+pub struct LoadedDetails {
+    pub branch: String,
+    /// Branch commit the details were loaded for (reloaded when it moves)
+    pub commit: String,
+    pub result: Result<BranchDetails, String>,
+}
+
+/// A worktree in the delete dialog, with what removing it would lose
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteTarget {
+    pub branch: String,
+    pub path: PathBuf,
+    /// Changed and untracked files (None if `git status` failed)
+    pub status: Option<WorktreeStatus>,
+    /// Commits of the branch that are on no remote
+    pub unpushed: usize,
+    /// Base branch the merge was checked against (e.g., "origin/main")
+    pub base: Option<String>,
+    /// Whether the branch is merged into `base`
+    pub merged: bool,
+}
+
+impl DeleteTarget {
+    /// Whether the worktree has uncommitted changes (or they couldn't be checked)
+    pub fn is_dirty(&self) -> bool {
+        self.status
+            .as_ref()
+            .is_none_or(|status| status.changed > 0 || status.untracked > 0)
+    }
+
+    /// What would be lost, e.g. ["2 modified", "1 unpushed commit"]; empty if nothing
+    pub fn risks(&self) -> Vec<String> {
+        let mut risks = Vec::new();
+        match &self.status {
+            Some(status) => {
+                if status.changed > 0 {
+                    risks.push(format!("{} modified", status.changed));
+                }
+                if status.untracked > 0 {
+                    risks.push(format!("{} untracked", status.untracked));
+                }
+            }
+            None => risks.push("status unknown".to_string()),
+        }
+        if self.unpushed > 0 {
+            let plural = if self.unpushed == 1 { "" } else { "s" };
+            risks.push(format!("{} unpushed commit{}", self.unpushed, plural));
+        }
+        risks
+    }
+}
+
+/// State of the delete confirmation dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteConfirmState {
+    /// Worktrees to delete
+    pub targets: Vec<DeleteTarget>,
+    /// Remove worktrees with uncommitted changes and delete unmerged branches
+    pub force: bool,
+    /// Also delete the local branches
+    pub delete_branch: bool,
+    /// Worktrees are moved to the trash rather than deleted
+    pub trash: bool,
+    /// User input (must be "yes" to proceed)
+    pub input: String,
+}
+
+impl DeleteConfirmState {
+    pub fn new(targets: Vec<DeleteTarget>, trash: bool) -> Self {
+        Self {
+            targets,
+            force: false,
+            delete_branch: false,
+            trash,
+            input: String::new(),
+        }
+    }
+
+    /// How a target is removed, or None if it is kept
+    ///
+    /// Without force, dirty worktrees are kept (unless they go to the trash) and
+    /// unmerged branches survive the removal of their worktree.
+    pub fn options_for(&self, target: &DeleteTarget) -> Option<RemoveOptions> {
+        if target.is_dirty() && !self.force && !self.trash {
+            return None;
+        }
+        Some(RemoveOptions {
+            force: self.force,
+            delete_branch: self.delete_branch && (target.merged || self.force),
+        })
+    }
+}
+
+/// State of the trash view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashViewState {
+    /// Removed worktrees, most recent first
+    pub entries: Vec<TrashEntry>,
+    /// Index of the highlighted entry
+    pub selected: usize,
+}
+
+/// State of the ignore list manager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreListState {
+    /// All known branches, sorted by name
+    pub branches: Vec<RemoteBranch>,
+    /// The ignore patterns, compiled when they change
+    pub patterns: CompiledPatterns,
+    /// Per branch, the ignore pattern deciding it (see [`CompiledPatterns::deciding`])
+    pub deciding: Vec<Option<usize>>,
+    /// Per branch, the other filter hiding it (see [`Config::hiding_filter`])
+    pub filtered_by: Vec<Option<&'static str>>,
+    /// Indices of the hidden branches
+    pub hidden: Vec<usize>,
+    /// Config layer the ignore patterns come from
+    pub source: ConfigLayer,
+    /// Index of the highlighted row: the patterns, then the ignored branches
+    pub selected: usize,
+    /// Pattern being added or edited
+    pub editor: Option<PatternEditor>,
+    /// Why the last edit was refused
+    pub error: Option<String>,
+}
+
+/// A pattern being typed in the ignore list manager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternEditor {
+    /// Index of the edited pattern (None when adding one)
+    pub index: Option<usize>,
+    pub input: String,
+    /// Why the input is not a valid pattern
+    pub invalid: Option<String>,
+    /// Number of branches the input matches
+    pub matching: usize,
+    /// Indices of the branches hidden with the edit applied
+    pub hidden: Vec<usize>,
+}
+
+impl IgnoreListState {
+    pub fn new(mut branches: Vec<RemoteBranch>, config: &Config) -> Self {
+        branches.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut state = Self {
+            branches,
+            patterns: CompiledPatterns::default(),
+            deciding: Vec::new(),
+            filtered_by: Vec::new(),
+            hidden: Vec::new(),
+            source: config.source("ignore_patterns"),
+            selected: 0,
+            editor: None,
+            error: None,
+        };
+        state.refresh(config);
+        state
+    }
+
+    /// Work out again what the filters hide, after the config changed
+    pub fn refresh(&mut self, config: &Config) {
+        self.patterns = CompiledPatterns::new(&config.ignore_patterns);
+        self.deciding = self
+            .branches
+            .iter()
+            .map(|branch| self.patterns.deciding(&branch.name))
+            .collect();
+        self.filtered_by = self
+            .branches
+            .iter()
+            .map(|branch| config.hiding_filter(branch))
+            .collect();
+        self.hidden = (0..self.branches.len())
+            .filter(|&i| config.should_ignore(&self.branches[i]))
+            .collect();
+    }
+
+    /// Branches a pattern has the last word on: hidden by it, or kept by a
+    /// negated one
+    pub fn decided_by(&self, index: usize) -> impl Iterator<Item = &RemoteBranch> {
+        self.branches
+            .iter()
+            .zip(&self.deciding)
+            .filter(move |(_, deciding)| **deciding == Some(index))
+            .map(|(branch, _)| branch)
+    }
+
+    /// Start adding (`index` None) or editing a pattern
+    pub fn edit(&mut self, index: Option<usize>, input: String) {
+        self.editor = Some(PatternEditor {
+            index,
+            input,
+            invalid: None,
+            matching: 0,
+            hidden: Vec::new(),
+        });
+        self.update_preview();
+    }
+
+    /// Work out what the pattern being typed would change (once per keystroke)
+    pub fn update_preview(&mut self) {
+        let Some(editor) = self.editor.as_mut() else {
+            return;
+        };
+        let input = editor.input.trim();
+        let alone = CompiledPatterns::new(&[input.trim_start_matches('!').to_string()]);
+        let edited = self.patterns.with_pattern(editor.index, input);
+
+        editor.invalid = alone.error(0).map(str::to_string);
+        editor.matching = if input.is_empty() {
+            0
+        } else {
+            self.branches
+                .iter()
+                .filter(|branch| alone.matches(&branch.name))
+                .count()
+        };
+        editor.hidden = (0..self.branches.len())
+            .filter(|&i| edited.matches(&self.branches[i].name) || self.filtered_by[i].is_some())
+            .collect();
+    }
 }
 
 /// State for the create new worktree dialog (2-step wizard)
@@ -186,6 +424,7 @@ pub enum SettingsField {
     BaseBranch,
     PostCreateCommand,
     AutoCreate,
+    ConcurrentJobs,
 }
 
 impl SettingsField {
@@ -197,6 +436,7 @@ impl SettingsField {
             SettingsField::BaseBranch,
             SettingsField::PostCreateCommand,
             SettingsField::AutoCreate,
+            SettingsField::ConcurrentJobs,
         ]
     }
 
@@ -208,6 +448,7 @@ impl SettingsField {
             SettingsField::BaseBranch => 3,
             SettingsField::PostCreateCommand => 4,
             SettingsField::AutoCreate => 5,
+            SettingsField::ConcurrentJobs => 6,
         }
     }
 
@@ -219,6 +460,7 @@ impl SettingsField {
             3 => SettingsField::BaseBranch,
             4 => SettingsField::PostCreateCommand,
             5 => SettingsField::AutoCreate,
+            6 => SettingsField::ConcurrentJobs,
             _ => SettingsField::Remote,
         }
     }
@@ -253,3 +495,96 @@ impl Default for SettingsState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(status: Option<WorktreeStatus>, unpushed: usize, merged: bool) -> DeleteTarget {
+        DeleteTarget {
+            branch: "feature/x".to_string(),
+            path: PathBuf::from("/tmp/feature-x"),
+            status,
+            unpushed,
+            base: Some("origin/main".to_string()),
+            merged,
+        }
+    }
+
+    fn options(force: bool, delete_branch: bool) -> RemoveOptions {
+        RemoveOptions {
+            force,
+            delete_branch,
+        }
+    }
+
+    #[test]
+    fn test_delete_target_risks() {
+        let clean = target(Some(WorktreeStatus::default()), 0, true);
+        assert!(!clean.is_dirty());
+        assert!(clean.risks().is_empty());
+
+        let status = WorktreeStatus {
+            changed: 2,
+            untracked: 1,
+            ..Default::default()
+        };
+        let dirty = target(Some(status), 1, false);
+        assert!(dirty.is_dirty());
+        assert_eq!(
+            dirty.risks(),
+            ["2 modified", "1 untracked", "1 unpushed commit"]
+        );
+
+        // Unpushed commits alone don't make a worktree dirty
+        let unpushed = target(Some(WorktreeStatus::default()), 3, false);
+        assert!(!unpushed.is_dirty());
+        assert_eq!(unpushed.risks(), ["3 unpushed commits"]);
+
+        // Without a status the worktree counts as dirty
+        let unknown = target(None, 0, true);
+        assert!(unknown.is_dirty());
+        assert_eq!(unknown.risks(), ["status unknown"]);
+    }
+
+    #[test]
+    fn test_delete_options() {
+        let clean_merged = target(Some(WorktreeStatus::default()), 0, true);
+        let clean_unmerged = target(Some(WorktreeStatus::default()), 2, false);
+        let status = WorktreeStatus {
+            untracked: 1,
+            ..Default::default()
+        };
+        let dirty = target(Some(status), 0, true);
+        let mut state = DeleteConfirmState::new(
+            vec![clean_merged.clone(), clean_unmerged.clone(), dirty.clone()],
+            false,
+        );
+
+        // Dirty worktrees are kept without force
+        assert_eq!(
+            state.options_for(&clean_merged),
+            Some(options(false, false))
+        );
+        assert_eq!(state.options_for(&dirty), None);
+
+        // Only merged branches are deleted without force
+        state.delete_branch = true;
+        assert_eq!(state.options_for(&clean_merged), Some(options(false, true)));
+        assert_eq!(
+            state.options_for(&clean_unmerged),
+            Some(options(false, false))
+        );
+
+        state.force = true;
+        assert_eq!(
+            state.options_for(&clean_unmerged),
+            Some(options(true, true))
+        );
+        assert_eq!(state.options_for(&dirty), Some(options(true, true)));
+
+        // The trash keeps the changes, so dirty worktrees may go there
+        let trash = DeleteConfirmState::new(vec![dirty.clone()], true);
+        assert_eq!(trash.options_for(&dirty), Some(options(false, false)));
+    }
+}
@@ -1,5 +1,6 @@
 //! View rendering functions
 
+use chrono::{DateTime, Utc};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -9,8 +10,12 @@ use ratatui::{
 };
 
 use super::App;
-use super::state::{CreateWorktreeState, CreateWorktreeStep};
-use crate::ui::{BranchListWidget, BranchLogWidget, ScrollableLogsWidget, StatusWidget};
+use super::state::{
+    CreateWorktreeState, CreateWorktreeStep, DeleteConfirmState, DetailPanel, TrashViewState,
+};
+use crate::ui::{
+    BranchDetailsWidget, BranchListWidget, BranchLogWidget, ScrollableLogsWidget, StatusWidget,
+};
 
 impl App {
     /// Render the main view
@@ -45,17 +50,36 @@ impl App {
             &mut self.branch_list_state,
         );
 
-        // Branch command log (right side)
+        // Branch command log or details (right side)
         let selected_branch = self.branch_list_state.selected_branch();
-        frame.render_widget(
-            BranchLogWidget::new(
-                &self.watcher.command_logs,
-                selected_branch.as_deref(),
-                &self.theme,
-                &mut self.branch_logs_state,
+        match self.detail_panel {
+            DetailPanel::Log => frame.render_widget(
+                BranchLogWidget::new(
+                    &self.watcher.command_logs,
+                    selected_branch.as_deref(),
+                    &self.theme,
+                    &mut self.branch_logs_state,
+                ),
+                split_chunks[1],
             ),
-            split_chunks[1],
-        );
+            DetailPanel::Details => {
+                // Stale details of the previously selected branch are not shown
+                let details = self
+                    .branch_details
+                    .as_ref()
+                    .filter(|d| selected_branch.as_deref() == Some(d.branch.as_str()))
+                    .map(|d| &d.result);
+                frame.render_widget(
+                    BranchDetailsWidget::new(
+                        selected_branch.as_deref(),
+                        details,
+                        &self.theme,
+                        &mut self.branch_logs_state,
+                    ),
+                    split_chunks[1],
+                );
+            }
+        }
 
         // Bottom logs (general git output)
         frame.render_widget(
@@ -81,6 +105,12 @@ impl App {
             Span::styled(" del ", Style::default().fg(self.theme.muted)),
             Span::styled("u", Style::default().fg(self.theme.primary)),
             Span::styled(" hide ", Style::default().fg(self.theme.muted)),
+            Span::styled("Space", Style::default().fg(self.theme.primary)),
+            Span::styled(" mark ", Style::default().fg(self.theme.muted)),
+            Span::styled("/", Style::default().fg(self.theme.primary)),
+            Span::styled(" filter ", Style::default().fg(self.theme.muted)),
+            Span::styled("Tab", Style::default().fg(self.theme.primary)),
+            Span::styled(" details ", Style::default().fg(self.theme.muted)),
             Span::styled("?", Style::default().fg(self.theme.primary)),
             Span::styled(" help ", Style::default().fg(self.theme.muted)),
             Span::styled("q", Style::default().fg(self.theme.primary)),
@@ -172,12 +202,141 @@ impl App {
         &self,
         frame: &mut Frame,
         area: Rect,
-        branch: String,
-        input: String,
+        state: &DeleteConfirmState,
     ) {
+        // Bulk deletes list the branches, up to a point
+        const MAX_LISTED: usize = 8;
+
+        let branch_style = Style::default()
+            .fg(self.theme.secondary)
+            .add_modifier(Modifier::BOLD);
+        let muted = Style::default().fg(self.theme.muted);
+        let warning = Style::default().fg(self.theme.warning);
+
+        let mut lines = vec![Line::raw("")];
+        if let [target] = state.targets.as_slice() {
+            lines.push(Line::from(vec![
+                Span::raw("Delete worktree for branch "),
+                Span::styled(target.branch.as_str(), branch_style),
+                Span::raw("?"),
+            ]));
+        } else {
+            lines.push(Line::raw(format!(
+                "Delete worktrees for {} branches?",
+                state.targets.len()
+            )));
+        }
+        lines.push(Line::raw(""));
+
+        // What each worktree holds that would be lost
+        for target in state.targets.iter().take(MAX_LISTED) {
+            let mut spans = Vec::new();
+            if state.targets.len() > 1 {
+                spans.push(Span::styled(format!("{} ", target.branch), branch_style));
+            }
+            let risks = target.risks();
+            if risks.is_empty() {
+                spans.push(Span::styled(
+                    "clean",
+                    Style::default().fg(self.theme.success),
+                ));
+            } else {
+                spans.push(Span::styled(risks.join(", "), warning));
+            }
+            spans.push(match &target.base {
+                Some(base) if target.merged => {
+                    Span::styled(format!(" · merged into {}", base), muted)
+                }
+                Some(base) => Span::styled(format!(" · not merged into {}", base), warning),
+                None => Span::styled(" · no base branch", muted),
+            });
+            match state.options_for(target) {
+                None => spans.push(Span::styled(" (kept)", muted)),
+                Some(options) if state.delete_branch && !options.delete_branch => {
+                    spans.push(Span::styled(" (branch kept)", muted))
+                }
+                Some(_) => {}
+            }
+            lines.push(Line::from(spans));
+        }
+        if state.targets.len() > MAX_LISTED {
+            lines.push(Line::styled(
+                format!("… and {} more", state.targets.len() - MAX_LISTED),
+                muted,
+            ));
+        }
+        lines.push(Line::raw(""));
+
+        let checkbox = |on: bool| if on { "[x] " } else { "[ ] " };
+        let key_style = Style::default()
+            .fg(self.theme.primary)
+            .add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::raw(checkbox(state.force)),
+            Span::styled("f", key_style),
+            Span::raw(" force: discard changes, delete unmerged branches"),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw(checkbox(state.delete_branch)),
+            Span::styled("b", key_style),
+            Span::raw(" also delete the local branch"),
+        ]));
+        lines.push(Line::raw(""));
+
+        let kept = state
+            .targets
+            .iter()
+            .filter(|target| state.options_for(target).is_none())
+            .count();
+        if kept == state.targets.len() {
+            lines.push(Line::styled(
+                "Uncommitted changes: press f to remove anyway",
+                warning,
+            ));
+        } else if kept > 0 {
+            lines.push(Line::styled(
+                if kept == 1 {
+                    "1 worktree with uncommitted changes is kept".to_string()
+                } else {
+                    format!("{} worktrees with uncommitted changes are kept", kept)
+                },
+                warning,
+            ));
+        } else if state.trash {
+            lines.push(Line::styled(
+                "Worktrees are moved to the trash (z brings them back)",
+                Style::default().fg(self.theme.secondary),
+            ));
+        } else if state.force && state.targets.iter().any(|t| !t.risks().is_empty()) {
+            lines.push(Line::styled(
+                "Uncommitted changes will be lost!",
+                Style::default()
+                    .fg(self.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        } else {
+            lines.push(Line::styled(
+                "This will remove the worktree directory!",
+                warning,
+            ));
+        }
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::raw("Type "),
+            Span::styled("yes", key_style),
+            Span::raw(" to confirm: "),
+            Span::styled(
+                state.input.as_str(),
+                Style::default()
+                    .fg(self.theme.fg)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            Span::styled("█", Style::default().fg(self.theme.primary)),
+        ]));
+
         // Center the popup
-        let popup_width = 60.min(area.width.saturating_sub(4));
-        let popup_height = 10;
+        let popup_width = 72.min(area.width.saturating_sub(4));
+        let popup_height = (lines.len() as u16 + 2).min(area.height);
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2;
@@ -205,43 +364,6 @@ impl App {
         let inner = block.inner(popup_area);
         frame.render_widget(block, popup_area);
 
-        let lines = vec![
-            Line::raw(""),
-            Line::from(vec![
-                Span::raw("Delete worktree for branch "),
-                Span::styled(
-                    &branch,
-                    Style::default()
-                        .fg(self.theme.secondary)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::raw("?"),
-            ]),
-            Line::raw(""),
-            Line::styled(
-                "This will remove the worktree directory!",
-                Style::default().fg(self.theme.warning),
-            ),
-            Line::raw(""),
-            Line::from(vec![
-                Span::raw("Type "),
-                Span::styled(
-                    "yes",
-                    Style::default()
-                        .fg(self.theme.primary)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::raw(" to confirm: "),
-                Span::styled(
-                    &input,
-                    Style::default()
-                        .fg(self.theme.fg)
-                        .add_modifier(Modifier::UNDERLINED),
-                ),
-                Span::styled("█", Style::default().fg(self.theme.primary)),
-            ]),
-        ];
-
         let paragraph = Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center);
         frame.render_widget(paragraph, inner);
     }
@@ -509,4 +631,123 @@ impl App {
             chunks[5],
         );
     }
+
+    /// Render the trash view
+    pub(super) fn render_trash(&self, frame: &mut Frame, area: Rect, state: &TrashViewState) {
+        let popup_width = 80.min(area.width.saturating_sub(4));
+        let popup_height = 18.min(area.height.saturating_sub(4));
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+
+        let popup_area = Rect {
+            x: area.x + popup_x,
+            y: area.y + popup_y,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.secondary))
+            .title(Span::styled(
+                " Trash ",
+                Style::default()
+                    .fg(self.theme.secondary)
+                    .add_modifier(Modifier::BOLD),
+            ));
+
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),    // Entry list
+                Constraint::Length(1), // Instructions
+            ])
+            .split(inner);
+
+        // Entry list
+        let visible_items: usize = chunks[0].height as usize;
+        let start_idx = if state.selected >= visible_items {
+            state.selected - visible_items + 1
+        } else {
+            0
+        };
+
+        let items: Vec<ListItem> = state
+            .entries
+            .iter()
+            .enumerate()
+            .skip(start_idx)
+            .take(visible_items)
+            .map(|(i, entry)| {
+                let is_selected = i == state.selected;
+                let (prefix, name_style) = if is_selected {
+                    (
+                        "▸ ",
+                        Style::default()
+                            .fg(self.theme.primary)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    ("  ", Style::default().fg(self.theme.fg))
+                };
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{}{}", prefix, entry.branch), name_style),
+                    Span::styled(
+                        format!("  {}", format_age(entry.removed_at)),
+                        Style::default().fg(self.theme.secondary),
+                    ),
+                    Span::styled(
+                        format!("  {}", entry.path.display()),
+                        Style::default().fg(self.theme.muted),
+                    ),
+                ]))
+            })
+            .collect();
+
+        if items.is_empty() {
+            frame.render_widget(
+                Paragraph::new(Line::from(Span::styled(
+                    "  The trash is empty",
+                    Style::default().fg(self.theme.muted),
+                ))),
+                chunks[0],
+            );
+        } else {
+            frame.render_widget(List::new(items), chunks[0]);
+        }
+
+        // Instructions
+        let instructions = Line::from(vec![
+            Span::styled("↑↓", Style::default().fg(self.theme.primary)),
+            Span::styled(" select  ", Style::default().fg(self.theme.muted)),
+            Span::styled("Enter", Style::default().fg(self.theme.primary)),
+            Span::styled(" restore  ", Style::default().fg(self.theme.muted)),
+            Span::styled("Esc", Style::default().fg(self.theme.primary)),
+            Span::styled(" close", Style::default().fg(self.theme.muted)),
+        ]);
+        frame.render_widget(
+            Paragraph::new(instructions).alignment(ratatui::layout::Alignment::Center),
+            chunks[1],
+        );
+    }
+}
+
+/// How long ago a point in time was, e.g. "5m ago"
+fn format_age(time: DateTime<Utc>) -> String {
+    let duration = Utc::now().signed_duration_since(time);
+
+    if duration.num_minutes() < 60 {
+        format!("{}m ago", duration.num_minutes())
+    } else if duration.num_hours() < 48 {
+        format!("{}h ago", duration.num_hours())
+    } else {
+        format!("{}d ago", duration.num_days())
+    }
 }
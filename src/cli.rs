@@ -0,0 +1,848 @@
+//! Non-interactive subcommands
+//!
+//! Lets scripts and CI jobs drive gwa without a terminal. Every command exits
+//! with one of the codes in [`exit_code`] so callers can branch on the result.
+//! Listing commands can emit JSON (`--format json`) for editor plugins and dashboards.
+
+use chrono::{DateTime, Utc};
+use clap::{Subcommand, ValueEnum};
+use color_eyre::eyre::Result;
+use serde::Serialize;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+
+use crate::config::{Config, ConfigLayer, FileReport, HookStage};
+use crate::control;
+use crate::daemon;
+use crate::executor::{CommandExecutor, CommandOutput};
+use crate::git::{RemoteBranch, Repository, WorktreeAgent, WorktreeInfo, fetch_remote};
+use crate::runtime::{HookRun, RuntimeState};
+use crate::trash::{Trash, TrashEntry};
+use crate::ui::{BranchStatus, StaleReason};
+use crate::watcher::{HookContext, Watcher};
+
+/// Version of the JSON documents printed with `--format json`
+///
+/// Bump this whenever a field is renamed or removed; adding fields is compatible.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// How many hook runs `gwa status` lists
+const RECENT_HOOKS: usize = 5;
+
+/// Output format for listing commands
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// Versioned JSON document
+    Json,
+}
+
+/// Top-level JSON document wrapping every machine-readable output
+#[derive(Serialize)]
+pub struct Document<T: Serialize> {
+    schema_version: u32,
+    #[serde(flatten)]
+    data: T,
+}
+
+impl<T: Serialize> Document<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            data,
+        }
+    }
+}
+
+/// Print a JSON document to stdout
+pub fn print_json<T: Serialize>(data: T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&Document::new(data))?);
+    Ok(())
+}
+
+/// A branch as reported by `gwa list --format json`
+#[derive(Serialize)]
+pub struct BranchEntry<'a> {
+    #[serde(flatten)]
+    branch: &'a RemoteBranch,
+    status: BranchStatus,
+    is_default: bool,
+    is_ignored: bool,
+    worktree: Option<&'a WorktreeInfo>,
+    /// Why the worktree was not fast-forwarded (only known to a running watcher)
+    stale: Option<StaleReason>,
+    /// When the worktree's remote branch was found deleted (cleanup policy)
+    orphaned_since: Option<DateTime<Utc>>,
+}
+
+/// Branch listing as reported by `gwa list --format json`
+#[derive(Serialize)]
+pub struct BranchList<'a> {
+    pub branches: Vec<BranchEntry<'a>>,
+}
+
+/// Configuration as reported by `gwa --show-config --format json`
+#[derive(Serialize)]
+pub struct ConfigReport<'a> {
+    pub config: &'a Config,
+    /// Layer each value not left at its default was set by
+    pub sources: &'a BTreeMap<String, ConfigLayer>,
+    pub worktrees: &'a [WorktreeInfo],
+}
+
+/// Repository summary as reported by `gwa status --format json`
+#[derive(Serialize)]
+struct StatusReport<'a> {
+    repository: PathBuf,
+    remote: &'a str,
+    remotes: &'a [String],
+    last_fetch: Option<DateTime<Utc>>,
+    auto_create: bool,
+    remote_branch_count: usize,
+    worktrees: &'a [WorktreeInfo],
+    /// Most recent hook runs, oldest first
+    hook_history: &'a [HookRun],
+}
+
+/// Config file problems as reported by `gwa config validate --format json`
+#[derive(Serialize)]
+struct ConfigValidation<'a> {
+    files: &'a [FileReport],
+}
+
+/// Trash contents as reported by `gwa restore --list --format json`
+#[derive(Serialize)]
+struct TrashList<'a> {
+    entries: &'a [TrashEntry],
+}
+
+/// Exit codes returned by the subcommands
+pub mod exit_code {
+    /// Command completed successfully
+    pub const SUCCESS: u8 = 0;
+    /// Generic failure (git error, I/O error, ...)
+    pub const FAILURE: u8 = 1;
+    /// The requested branch or worktree does not exist
+    pub const NOT_FOUND: u8 = 3;
+    /// A hook failed; a failing pre-create/pre-remove hook cancels the action
+    pub const HOOK_FAILED: u8 = 4;
+}
+
+/// Subcommands for scripting gwa without the TUI
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List branches and their worktrees
+    List {
+        /// Include branches matched by the ignore patterns
+        #[arg(long)]
+        all: bool,
+    },
+    /// Create a worktree for a branch
+    Create {
+        /// Branch to check out (remote or local)
+        branch: String,
+        /// Create a new branch from this base instead of checking out an existing one
+        #[arg(long)]
+        from: Option<String>,
+        /// Don't run the pre-create and post-create hooks
+        #[arg(long)]
+        no_hook: bool,
+    },
+    /// Remove the worktree for a branch
+    Remove {
+        /// Branch whose worktree should be removed
+        branch: String,
+        /// Remove even if the worktree has uncommitted changes
+        #[arg(short, long)]
+        force: bool,
+        /// Don't run the pre-remove and post-remove hooks
+        #[arg(long)]
+        no_hook: bool,
+    },
+    /// Bring back a worktree removed to the trash
+    Restore {
+        /// Branch whose most recently removed worktree should be restored
+        #[arg(required_unless_present = "list")]
+        branch: Option<String>,
+        /// List the worktrees in the trash instead
+        #[arg(long)]
+        list: bool,
+    },
+    /// Fetch from the configured remotes
+    Fetch,
+    /// Show repository and worktree status
+    Status,
+    /// Watch the remote and create worktrees without the TUI
+    Daemon {
+        /// Write logs to this file instead of stdout
+        #[arg(long)]
+        log_file: Option<PathBuf>,
+    },
+    /// Cancel the hook running for a branch in a running gwa instance
+    Cancel {
+        /// Branch whose hook should be stopped
+        branch: String,
+    },
+    /// Inspect the config files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Call a method on a running gwa instance through its control socket
+    Ctl {
+        /// Method name (fetch, queue, cancel_hook, statuses, subscribe)
+        method: String,
+        /// Branch parameter for queue and cancel_hook
+        #[arg(long)]
+        branch: Option<String>,
+    },
+}
+
+/// Subcommands of `gwa config`
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Check the config files for errors, unknown keys and outdated versions
+    Validate,
+}
+
+/// Run a subcommand and return the process exit code
+pub fn run(command: &Command, repo_path: &Path, format: OutputFormat) -> Result<ExitCode> {
+    let repo = Repository::discover(repo_path)?;
+
+    // Config commands have to work on files that don't load
+    if let Command::Config { command } = command {
+        let code = match command {
+            ConfigCommand::Validate => validate_config(&repo, format)?,
+        };
+        return Ok(ExitCode::from(code));
+    }
+
+    let config = Config::load(repo.main_root())?;
+
+    let code = match command {
+        Command::List { all } => list(&repo, &config, *all, format)?,
+        Command::Create {
+            branch,
+            from,
+            no_hook,
+        } => create(&repo, &config, branch, from.as_deref(), *no_hook)?,
+        Command::Remove {
+            branch,
+            force,
+            no_hook,
+        } => remove(&repo, &config, branch, *force, *no_hook)?,
+        Command::Restore { branch, list } => match branch {
+            Some(branch) if !list => restore(&repo, branch)?,
+            _ => list_trash(&repo, format)?,
+        },
+        Command::Config { .. } => unreachable!("handled before loading the config"),
+        Command::Fetch => fetch(&repo, &config)?,
+        Command::Status => status(&repo, &config, format)?,
+        Command::Daemon { .. } => {
+            daemon::run(repo, config)?;
+            exit_code::SUCCESS
+        }
+        Command::Cancel { branch } => control::run_client(
+            repo.main_root(),
+            "cancel_hook",
+            serde_json::json!({ "branch": branch }),
+        )?,
+        Command::Ctl { method, branch } => {
+            let params = match branch {
+                Some(branch) => serde_json::json!({ "branch": branch }),
+                None => serde_json::Value::Null,
+            };
+            control::run_client(repo.main_root(), method, params)?
+        }
+    };
+
+    Ok(ExitCode::from(code))
+}
+
+/// Print every known branch with its status and worktree path (if any)
+fn list(repo: &Repository, config: &Config, all: bool, format: OutputFormat) -> Result<u8> {
+    let mut watcher = Watcher::new();
+    watcher.init(repo, config)?;
+    watcher.restore_orphans(&RuntimeState::load(repo));
+
+    let worktrees = WorktreeAgent::new(repo).list()?;
+
+    let entries = branch_entries(&watcher, config, &worktrees, all);
+
+    if format == OutputFormat::Json {
+        print_json(BranchList { branches: entries })?;
+        return Ok(exit_code::SUCCESS);
+    }
+
+    for entry in &entries {
+        match entry.worktree {
+            Some(wt) => println!("● {}\t{}", entry.branch.name, wt.path.display()),
+            None => println!("○ {}", entry.branch.name),
+        }
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Build the sorted list of branches with their computed status
+pub fn branch_entries<'a>(
+    watcher: &'a Watcher,
+    config: &Config,
+    worktrees: &'a [WorktreeInfo],
+    include_ignored: bool,
+) -> Vec<BranchEntry<'a>> {
+    let mut branches = watcher.get_known_branches();
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    branches
+        .into_iter()
+        .map(|branch| {
+            let worktree = find_worktree(worktrees, &branch.name);
+            BranchEntry {
+                branch,
+                status: watcher.branch_status(&branch.name, worktree),
+                is_default: config.is_base_branch(branch),
+                is_ignored: config.should_ignore(branch),
+                worktree,
+                stale: watcher.stale_reason(&branch.name),
+                orphaned_since: watcher.orphaned_since(&branch.name),
+            }
+        })
+        .filter(|entry| include_ignored || !entry.is_ignored)
+        .collect()
+}
+
+/// Create a worktree and run the post-create command
+fn create(
+    repo: &Repository,
+    config: &Config,
+    branch: &str,
+    from: Option<&str>,
+    no_hook: bool,
+) -> Result<u8> {
+    let worktree_agent = WorktreeAgent::new(repo);
+    let worktree_path = config.get_worktree_path(repo.main_root(), branch);
+
+    if worktree_agent.has_worktree_for_branch(branch)? {
+        eprintln!("Branch '{}' already has a worktree", branch);
+        return Ok(exit_code::FAILURE);
+    }
+
+    let local_branches = repo.get_local_branches()?;
+    let is_local = |name: &str| local_branches.iter().any(|b| b.name == name);
+
+    let mut watcher = Watcher::new();
+    watcher.init(repo, config)?;
+    let upstream = watcher.upstream(repo, config, branch);
+
+    // Prefer a local base branch, fall back to the remote one
+    let base_ref = from.map(|base| {
+        if is_local(base) {
+            base.to_string()
+        } else {
+            watcher.upstream(repo, config, base).tracking_ref()
+        }
+    });
+
+    if base_ref.is_none()
+        && !is_local(branch)
+        && repo.resolve_commit(&upstream.tracking_ref()).is_none()
+    {
+        eprintln!(
+            "Branch '{}' not found locally or on '{}'",
+            branch, upstream.remote
+        );
+        return Ok(exit_code::NOT_FOUND);
+    }
+
+    let mut hook = HookContext {
+        stage: HookStage::PreCreate,
+        branch,
+        worktree_path: &worktree_path,
+        base_ref: base_ref.as_deref(),
+        upstream: &upstream,
+    };
+
+    if !no_hook && run_hook(repo, config, &hook)? != 0 {
+        return Ok(exit_code::HOOK_FAILED);
+    }
+
+    let result = match hook.base_ref {
+        Some(base_ref) => worktree_agent.create_new_branch(branch, base_ref, &worktree_path),
+        None => worktree_agent.create(branch, &worktree_path, &upstream.tracking_ref()),
+    };
+
+    match result {
+        Ok(log_messages) => {
+            for msg in log_messages {
+                println!("{}", msg);
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to create worktree: {}", e);
+            return Ok(exit_code::FAILURE);
+        }
+    }
+
+    hook.stage = HookStage::PostCreate;
+    if !no_hook && run_hook(repo, config, &hook)? != 0 {
+        return Ok(exit_code::HOOK_FAILED);
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Run the hook for a stage to completion, streaming its output to the terminal
+///
+/// Returns 0 if no hook is configured for the stage. Ctrl-C stops the hook (it runs
+/// in its own process group, so the terminal doesn't signal it directly).
+fn run_hook(repo: &Repository, config: &Config, hook: &HookContext) -> Result<i32> {
+    let Some(command) = config.hook_command(hook.stage) else {
+        return Ok(0);
+    };
+
+    // Only while the hook runs: afterwards Ctrl-C ends gwa as usual
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let mut handlers = Vec::new();
+    for signal in [SIGINT, SIGTERM] {
+        handlers.push(signal_hook::flag::register(
+            signal,
+            Arc::clone(&interrupted),
+        )?);
+    }
+
+    let exit = wait_for_hook(repo, config, hook, command, &interrupted);
+    for handler in handlers {
+        signal_hook::low_level::unregister(handler);
+    }
+    let exit = exit?;
+
+    if exit != 0 {
+        eprintln!("The {} hook failed (exit code: {})", hook.stage, exit);
+    }
+
+    Ok(exit)
+}
+
+/// Start a hook command and stream its output until it exits, cancelling it
+/// once `interrupted` is set
+fn wait_for_hook(
+    repo: &Repository,
+    config: &Config,
+    hook: &HookContext,
+    command: &str,
+    interrupted: &AtomicBool,
+) -> Result<i32> {
+    println!("[{}] $ {}", hook.stage, command);
+    let running = CommandExecutor::run_async(
+        command.to_string(),
+        &hook.working_dir(repo),
+        hook.env(repo, config),
+        config.hook_timeout(),
+    )?;
+
+    let mut exit = -1;
+    loop {
+        if interrupted.load(Ordering::Relaxed) {
+            running.cancel();
+        }
+
+        let output = match running.output_rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(output) => output,
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+
+        match output {
+            CommandOutput::Stdout(line) => println!("{}", line),
+            CommandOutput::Stderr(line) => eprintln!("{}", line),
+            CommandOutput::Exit(code) => {
+                exit = code;
+                break;
+            }
+            CommandOutput::Error(msg) => {
+                eprintln!("Error: {}", msg);
+                break;
+            }
+        }
+    }
+
+    // A stopped hook's process group is only killed off after the grace period
+    running.wait();
+    Ok(exit)
+}
+
+/// Remove the worktree checked out for a branch, running the remove hooks
+fn remove(
+    repo: &Repository,
+    config: &Config,
+    branch: &str,
+    force: bool,
+    no_hook: bool,
+) -> Result<u8> {
+    let worktree_agent = WorktreeAgent::new(repo);
+    let worktrees = worktree_agent.list()?;
+
+    let Some(wt) = find_worktree(&worktrees, branch) else {
+        eprintln!("No worktree found for branch '{}'", branch);
+        return Ok(exit_code::NOT_FOUND);
+    };
+
+    if wt.is_main {
+        eprintln!("Cannot remove the main worktree");
+        return Ok(exit_code::FAILURE);
+    }
+
+    let mut watcher = Watcher::new();
+    watcher.init(repo, config)?;
+    let upstream = watcher.upstream(repo, config, branch);
+
+    let mut hook = HookContext {
+        stage: HookStage::PreRemove,
+        branch,
+        worktree_path: &wt.path,
+        base_ref: None,
+        upstream: &upstream,
+    };
+
+    if !no_hook && run_hook(repo, config, &hook)? != 0 {
+        return Ok(exit_code::HOOK_FAILED);
+    }
+
+    if config.trash && wt.path.exists() {
+        match Trash::new(repo).put(repo, branch, &wt.path) {
+            Ok(_) => println!(
+                "Moved worktree at {} to the trash (gwa restore {} brings it back)",
+                wt.path.display(),
+                branch
+            ),
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(exit_code::FAILURE);
+            }
+        }
+    } else {
+        if let Err(e) = worktree_agent.remove(&wt.path, force) {
+            eprintln!("{}", e);
+            return Ok(exit_code::FAILURE);
+        }
+        println!("Removed worktree at {}", wt.path.display());
+    }
+
+    hook.stage = HookStage::PostRemove;
+    if !no_hook && run_hook(repo, config, &hook)? != 0 {
+        return Ok(exit_code::HOOK_FAILED);
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Move the most recently trashed worktree of a branch back into place
+///
+/// Hooks don't run: the worktree comes back exactly as it was removed.
+fn restore(repo: &Repository, branch: &str) -> Result<u8> {
+    let trash = Trash::new(repo);
+    let Some(entry) = trash.find(branch) else {
+        eprintln!("No worktree of '{}' in the trash", branch);
+        return Ok(exit_code::NOT_FOUND);
+    };
+
+    match trash.restore(repo, &entry) {
+        Ok(log_messages) => {
+            for msg in log_messages {
+                println!("{}", msg);
+            }
+            Ok(exit_code::SUCCESS)
+        }
+        Err(e) => {
+            eprintln!("Failed to restore worktree: {}", e);
+            Ok(exit_code::FAILURE)
+        }
+    }
+}
+
+/// Print the worktrees in the trash, most recently removed first
+fn list_trash(repo: &Repository, format: OutputFormat) -> Result<u8> {
+    let entries = Trash::new(repo).list();
+
+    if format == OutputFormat::Json {
+        print_json(TrashList { entries: &entries })?;
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if entries.is_empty() {
+        println!("The trash is empty");
+    }
+    for entry in &entries {
+        println!(
+            "{}\t{}\t{}",
+            entry.branch,
+            entry.removed_at.to_rfc3339(),
+            entry.path.display()
+        );
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+/// Report the problems found in each config file
+fn validate_config(repo: &Repository, format: OutputFormat) -> Result<u8> {
+    let reports = Config::validate(repo.main_root())?;
+    let code = if reports.iter().all(FileReport::is_valid) {
+        exit_code::SUCCESS
+    } else {
+        exit_code::FAILURE
+    };
+
+    if format == OutputFormat::Json {
+        print_json(ConfigValidation { files: &reports })?;
+        return Ok(code);
+    }
+
+    if reports.is_empty() {
+        println!("No config files found");
+    }
+    for report in &reports {
+        let summary = if report.issues.is_empty() { ": ok" } else { "" };
+        println!(
+            "{} ({}){}",
+            report.path.display(),
+            report.layer.name(),
+            summary
+        );
+        for issue in &report.issues {
+            let position = match (issue.line, issue.column) {
+                (Some(line), Some(column)) => format!("line {}, column {}: ", line, column),
+                _ => String::new(),
+            };
+            println!("  {}: {}{}", issue.severity.name(), position, issue.message);
+        }
+    }
+
+    Ok(code)
+}
+
+/// Fetch from every watched remote and record the fetch time
+fn fetch(repo: &Repository, config: &Config) -> Result<u8> {
+    let remotes = config.remote_names();
+    for remote in &remotes {
+        if let Err(msg) = repo.validate_remote(remote) {
+            eprintln!("{}", msg);
+            return Ok(exit_code::NOT_FOUND);
+        }
+    }
+
+    let mut code = exit_code::SUCCESS;
+    for remote in &remotes {
+        match fetch_remote(repo.root(), remote) {
+            Ok(output) => {
+                if let Some(msg) = output {
+                    println!("{}", msg);
+                }
+                println!("Fetched {}", remote);
+            }
+            Err(msg) => {
+                eprintln!("Fetch of {} failed: {}", remote, msg.trim());
+                code = exit_code::FAILURE;
+            }
+        }
+    }
+
+    if code == exit_code::SUCCESS {
+        RuntimeState::update(repo, |state| state.last_fetch = Some(Utc::now()))?;
+    }
+
+    Ok(code)
+}
+
+/// Print a summary of the repository and its worktrees
+fn status(repo: &Repository, config: &Config, format: OutputFormat) -> Result<u8> {
+    let remotes = config.remote_names();
+    let mut remote_branch_count = 0;
+    for remote in &remotes {
+        remote_branch_count += repo.get_remote_branches(remote)?.len();
+    }
+    let worktrees = WorktreeAgent::new(repo).list()?;
+    let state = RuntimeState::load(repo);
+
+    if format == OutputFormat::Json {
+        print_json(StatusReport {
+            repository: repo.main_root().to_path_buf(),
+            remote: &config.remote_name,
+            remotes: &remotes,
+            last_fetch: state.last_fetch,
+            auto_create: config.auto_create_worktrees,
+            remote_branch_count,
+            worktrees: &worktrees,
+            hook_history: &state.hook_history,
+        })?;
+        return Ok(exit_code::SUCCESS);
+    }
+
+    println!("Repository: {}", repo.main_root().display());
+    println!("Remote: {}", remotes.join(", "));
+    println!(
+        "Last fetch: {}",
+        state
+            .last_fetch
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| "never".to_string())
+    );
+    println!(
+        "Auto-create: {}",
+        if config.auto_create_worktrees {
+            "on"
+        } else {
+            "off"
+        }
+    );
+    println!("Remote branches: {}", remote_branch_count);
+    println!();
+    println!("Worktrees ({}):", worktrees.len());
+    for wt in &worktrees {
+        let mut flags = Vec::new();
+        if wt.is_main {
+            flags.push("main");
+        }
+        if wt.is_locked {
+            flags.push("locked");
+        }
+        if wt.is_prunable {
+            flags.push("prunable");
+        }
+
+        let flags = if flags.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", flags.join(", "))
+        };
+
+        println!(
+            "  {} -> {}{}",
+            wt.branch.as_deref().unwrap_or("(detached)"),
+            wt.path.display(),
+            flags
+        );
+    }
+
+    if !state.hook_history.is_empty() {
+        println!();
+        println!("Recent hooks:");
+        for run in state.hook_history.iter().rev().take(RECENT_HOOKS) {
+            println!(
+                "  {} {} {} -> {}",
+                run.finished_at.to_rfc3339(),
+                run.stage,
+                run.branch,
+                if run.exit_code == 0 {
+                    "ok".to_string()
+                } else {
+                    format!("exit code {}", run.exit_code)
+                }
+            );
+        }
+    }
+
+    Ok(exit_code::SUCCESS)
+}
+
+fn find_worktree<'a>(worktrees: &'a [WorktreeInfo], branch: &str) -> Option<&'a WorktreeInfo> {
+    worktrees
+        .iter()
+        .find(|w| w.branch.as_deref() == Some(branch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watcher::tests::{git, setup};
+    use std::fs;
+
+    #[test]
+    fn test_json_document_schema() {
+        let document = Document::new(BranchList {
+            branches: Vec::new(),
+        });
+        let value = serde_json::to_value(&document).unwrap();
+
+        assert_eq!(value["schema_version"], JSON_SCHEMA_VERSION);
+        assert!(value["branches"].is_array());
+        assert_eq!(
+            serde_json::to_value(BranchStatus::LocalPrunable).unwrap(),
+            "local_prunable"
+        );
+    }
+
+    #[test]
+    fn test_create_and_remove_exit_codes() {
+        let (dir, repo) = setup("cli", &["feature/x"]);
+        let config = Config::default();
+        let path = config.get_worktree_path(repo.main_root(), "feature/x");
+
+        let code = create(&repo, &config, "feature/x", None, false).unwrap();
+        assert_eq!(code, exit_code::SUCCESS);
+        assert!(path.exists());
+        assert_eq!(
+            git(&path, &["rev-parse", "--abbrev-ref", "HEAD"]),
+            "feature/x"
+        );
+
+        // Only one worktree per branch
+        let code = create(&repo, &config, "feature/x", None, false).unwrap();
+        assert_eq!(code, exit_code::FAILURE);
+
+        let code = create(&repo, &config, "feature/missing", None, false).unwrap();
+        assert_eq!(code, exit_code::NOT_FOUND);
+        let code = remove(&repo, &config, "feature/missing", false, false).unwrap();
+        assert_eq!(code, exit_code::NOT_FOUND);
+
+        let code = remove(&repo, &config, "feature/x", false, false).unwrap();
+        assert_eq!(code, exit_code::SUCCESS);
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failing_hooks_exit_codes() {
+        let (dir, repo) = setup("cli-hooks", &["feature/x", "feature/y"]);
+        let mut config = Config::default();
+        config.hooks.pre_create = Some("exit 1".to_string());
+        config.hooks.pre_remove = Some("exit 1".to_string());
+        let path = config.get_worktree_path(repo.main_root(), "feature/x");
+
+        // A failing pre-create hook cancels the create
+        let code = create(&repo, &config, "feature/x", None, false).unwrap();
+        assert_eq!(code, exit_code::HOOK_FAILED);
+        assert!(!path.exists());
+
+        // Unless hooks are skipped
+        let code = create(&repo, &config, "feature/x", None, true).unwrap();
+        assert_eq!(code, exit_code::SUCCESS);
+
+        // A failing pre-remove hook cancels the remove
+        let code = remove(&repo, &config, "feature/x", false, false).unwrap();
+        assert_eq!(code, exit_code::HOOK_FAILED);
+        assert!(path.exists());
+
+        // A failing post-create hook leaves the worktree in place
+        config.hooks.pre_create = None;
+        config.hooks.post_create = Some("exit 2".to_string());
+        let code = create(&repo, &config, "feature/y", None, false).unwrap();
+        assert_eq!(code, exit_code::HOOK_FAILED);
+        assert!(
+            config
+                .get_worktree_path(repo.main_root(), "feature/y")
+                .exists()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
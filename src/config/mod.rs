@@ -0,0 +1,1342 @@
+//! Configuration management for git-worktree-agent
+//!
+//! The configuration is layered, each layer overriding the ones before it:
+//! built-in defaults, the user's global config, a shared config committed to the
+//! repository and a gitignored local config with personal settings. Settings
+//! changed in gwa are saved to the local config, as far as they differ from the
+//! layers below.
+
+mod schema;
+
+pub use schema::FileReport;
+
+use color_eyre::eyre::{Context, Result, eyre};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::git::RemoteBranch;
+
+/// Version of the config file format written by this gwa
+pub const CONFIG_VERSION: u32 = 2;
+
+/// The name of the config file stored in the git repository root
+pub const CONFIG_FILE_NAME: &str = ".gwa-config.json";
+
+/// The name of the shared config file committed to the repository root
+pub const SHARED_CONFIG_FILE_NAME: &str = ".gwa.json";
+
+/// Where a config value comes from, lowest precedence first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLayer {
+    /// Built-in default
+    Default,
+    /// `~/.config/gwa/config.json`, for every repository of the user
+    Global,
+    /// `.gwa.json` in the repository root, shared by the team
+    Shared,
+    /// `.gwa-config.json` in the repository root, personal and gitignored
+    Local,
+}
+
+impl ConfigLayer {
+    /// The layers read from files, lowest precedence first
+    pub const FILES: [ConfigLayer; 3] =
+        [ConfigLayer::Global, ConfigLayer::Shared, ConfigLayer::Local];
+
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigLayer::Default => "default",
+            ConfigLayer::Global => "global",
+            ConfigLayer::Shared => "shared",
+            ConfigLayer::Local => "local",
+        }
+    }
+
+    /// The file of the layer (None for defaults, or if there is no home dir)
+    ///
+    /// The global config is in `$XDG_CONFIG_HOME/gwa`, falling back to
+    /// `~/.config/gwa` on every platform (not the macOS Application Support dir).
+    pub fn path(&self, repo_root: &Path) -> Option<PathBuf> {
+        match self {
+            ConfigLayer::Default => None,
+            ConfigLayer::Global => std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .filter(|dir| dir.is_absolute())
+                .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+                .map(|dir| dir.join("gwa").join("config.json")),
+            ConfigLayer::Shared => Some(repo_root.join(SHARED_CONFIG_FILE_NAME)),
+            ConfigLayer::Local => Some(repo_root.join(CONFIG_FILE_NAME)),
+        }
+    }
+}
+
+/// Main configuration structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Version of the config file format
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    /// Polling interval in seconds for checking remote branches
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_secs: u64,
+
+    /// How each poll checks the remotes for changes
+    #[serde(default)]
+    pub poll_mode: PollMode,
+
+    /// Commands to run at each stage of a worktree's lifecycle
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// What to do with a worktree when its remote branch gets new commits
+    #[serde(default)]
+    pub on_branch_update: UpdateAction,
+
+    /// What to do with a worktree once its remote branch was deleted
+    #[serde(default)]
+    pub cleanup: CleanupPolicy,
+
+    /// How long a remote branch must stay deleted before its worktree is removed
+    #[serde(default = "default_cleanup_grace_period")]
+    pub cleanup_grace_period_secs: u64,
+
+    /// Move removed worktrees to the trash instead of deleting them
+    #[serde(default)]
+    pub trash: bool,
+
+    /// How long removed worktrees stay in the trash
+    #[serde(default = "default_trash_retention")]
+    pub trash_retention_secs: u64,
+
+    /// Working directory relative to worktree root for running commands
+    #[serde(default)]
+    pub command_working_dir: Option<String>,
+
+    /// Patterns for branches to ignore (glob patterns or exact names)
+    /// Use 't' key to toggle branches, or add patterns like "dependabot/*"
+    #[serde(default = "default_ignore_patterns")]
+    pub ignore_patterns: Vec<String>,
+
+    /// Patterns for the only branches to watch (all if empty); base branches are
+    /// always watched
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+
+    /// Patterns for the only authors whose remote branches to watch (all if empty),
+    /// matched against the name and email of the branch's latest commit
+    #[serde(default)]
+    pub include_authors: Vec<String>,
+
+    /// Patterns for authors whose remote branches to ignore
+    #[serde(default)]
+    pub ignore_authors: Vec<String>,
+
+    /// Whether to auto-create worktrees for new branches
+    #[serde(default = "default_auto_create")]
+    pub auto_create_worktrees: bool,
+
+    /// How many queued branches may be created and set up (post-create hook) at once
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+
+    /// Order of the branch list in the TUI
+    #[serde(default)]
+    pub sort_mode: SortMode,
+
+    /// Group the branch list by path prefix (`feature/`, `fix/`, ...)
+    #[serde(default)]
+    pub group_by_prefix: bool,
+
+    /// Base directory for worktrees (relative to repo root, default: "../")
+    #[serde(default = "default_worktree_base")]
+    pub worktree_base_dir: String,
+
+    /// The base/main branch to watch from (e.g., "main" or "master")
+    #[serde(default)]
+    pub base_branch: Option<String>,
+
+    /// Remote name to watch (default: "origin")
+    #[serde(default = "default_remote")]
+    pub remote_name: String,
+
+    /// More remotes to watch besides `remote_name` (e.g., "upstream" or forks)
+    ///
+    /// Branches from these remotes are checked out as `<remote>/<branch>`, so they
+    /// can't collide with a branch of the same name on another remote. An entry
+    /// named like `remote_name` only adds settings for that remote.
+    #[serde(default)]
+    pub remotes: Vec<RemoteConfig>,
+
+    /// Merged values of the layers below the local config, which `save` leaves out
+    #[serde(skip)]
+    inherited: Option<Value>,
+
+    /// Layer each value was set by, keyed by its path (e.g., "hooks.post_create")
+    #[serde(skip)]
+    sources: BTreeMap<String, ConfigLayer>,
+
+    /// The branch and author patterns, compiled by `compile_patterns`
+    #[serde(skip)]
+    compiled: CompiledFilters,
+}
+
+/// The branch and author patterns of a config, compiled once instead of for
+/// every branch they are matched against
+#[derive(Debug, Clone, Default)]
+struct CompiledFilters {
+    ignore_patterns: CompiledPatterns,
+    include_patterns: CompiledPatterns,
+    include_authors: CompiledPatterns,
+    ignore_authors: CompiledPatterns,
+}
+
+/// How a poll checks a remote for changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollMode {
+    /// Run `git fetch --prune` on every poll
+    #[default]
+    Fetch,
+    /// Ask for the branch heads with `git ls-remote --heads` and only fetch the
+    /// branches that changed (much cheaper on large repositories)
+    LsRemote,
+}
+
+impl PollMode {
+    /// Name as written in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            PollMode::Fetch => "fetch",
+            PollMode::LsRemote => "ls_remote",
+        }
+    }
+}
+
+/// What to do with a worktree when its remote branch moves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateAction {
+    /// Only report the update
+    Notify,
+    /// Fast-forward the worktree to the new remote commit
+    FastForward,
+    /// Run the post-update hook in the worktree (if configured)
+    #[default]
+    Hook,
+}
+
+impl UpdateAction {
+    /// Name as written in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            UpdateAction::Notify => "notify",
+            UpdateAction::FastForward => "fast_forward",
+            UpdateAction::Hook => "hook",
+        }
+    }
+}
+
+/// Order of the branch list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// Local worktrees first, then by name
+    #[default]
+    Status,
+    /// By name
+    Name,
+    /// Most recent commit first
+    CommitDate,
+    /// By author of the latest commit
+    Author,
+}
+
+impl SortMode {
+    /// Name as written in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Status => "status",
+            SortMode::Name => "name",
+            SortMode::CommitDate => "commit_date",
+            SortMode::Author => "author",
+        }
+    }
+
+    /// The next mode when cycling through them
+    pub fn next(&self) -> Self {
+        match self {
+            SortMode::Status => SortMode::Name,
+            SortMode::Name => SortMode::CommitDate,
+            SortMode::CommitDate => SortMode::Author,
+            SortMode::Author => SortMode::Status,
+        }
+    }
+}
+
+/// What to do with a worktree whose remote branch was deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupPolicy {
+    /// Leave the worktree alone
+    #[default]
+    Off,
+    /// Show the worktree as orphaned in the branch list
+    Mark,
+    /// Mark it, then remove it after the grace period if it has no uncommitted
+    /// changes and its branch is merged into the base branch
+    #[serde(alias = "remove-if-clean-and-merged")]
+    RemoveIfCleanAndMerged,
+}
+
+impl CleanupPolicy {
+    /// Name as written in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            CleanupPolicy::Off => "off",
+            CleanupPolicy::Mark => "mark",
+            CleanupPolicy::RemoveIfCleanAndMerged => "remove_if_clean_and_merged",
+        }
+    }
+}
+
+/// A remote watched for new branches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RemoteConfig {
+    /// Remote name (e.g., "upstream")
+    pub name: String,
+    /// Patterns for branches on this remote to skip, matched against the branch
+    /// name on the remote (without the `<remote>/` prefix)
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// The base branch on this remote (default: `base_branch`)
+    #[serde(default)]
+    pub base_branch: Option<String>,
+}
+
+/// Where a local branch comes from: a watched remote and the branch's name there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upstream {
+    pub remote: String,
+    pub branch: String,
+}
+
+impl Upstream {
+    /// The remote-tracking ref (e.g., "refs/remotes/alice/feature/x")
+    ///
+    /// Spelled out in full since "alice/x" would also match the local branch of a
+    /// fork.
+    pub fn tracking_ref(&self) -> String {
+        format!("refs/remotes/{}/{}", self.remote, self.branch)
+    }
+}
+
+/// Lifecycle stage a hook command runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookStage {
+    /// Before `git worktree add`; a non-zero exit aborts the creation
+    PreCreate,
+    /// After the worktree was created
+    PostCreate,
+    /// Before `git worktree remove`; a non-zero exit aborts the removal
+    PreRemove,
+    /// After the worktree was removed
+    PostRemove,
+    /// After a fetch moved the remote branch a worktree tracks
+    PostUpdate,
+}
+
+impl HookStage {
+    /// All stages in the order they happen
+    pub const ALL: [HookStage; 5] = [
+        HookStage::PreCreate,
+        HookStage::PostCreate,
+        HookStage::PreRemove,
+        HookStage::PostRemove,
+        HookStage::PostUpdate,
+    ];
+
+    /// Human readable stage name (e.g., "pre-create")
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookStage::PreCreate => "pre-create",
+            HookStage::PostCreate => "post-create",
+            HookStage::PreRemove => "pre-remove",
+            HookStage::PostRemove => "post-remove",
+            HookStage::PostUpdate => "post-update",
+        }
+    }
+}
+
+impl std::fmt::Display for HookStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Hook commands for each lifecycle stage
+///
+/// Pre-create and post-remove hooks run in the main worktree (the worktree doesn't
+/// exist at that point), all other stages run in the branch's worktree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Validate a branch before its worktree is created
+    #[serde(default)]
+    pub pre_create: Option<String>,
+    /// Set up a freshly created worktree (e.g., "npm install")
+    #[serde(default)]
+    pub post_create: Option<String>,
+    /// Tear down services before a worktree is removed (e.g., "docker compose down")
+    #[serde(default)]
+    pub pre_remove: Option<String>,
+    /// Clean up after a worktree was removed (e.g., drop a per-branch database)
+    #[serde(default)]
+    pub post_remove: Option<String>,
+    /// React to new commits on the tracked remote branch
+    #[serde(default)]
+    pub post_update: Option<String>,
+    /// Stop a hook (and everything it started) after this many seconds
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+fn default_version() -> u32 {
+    CONFIG_VERSION
+}
+
+fn default_poll_interval() -> u64 {
+    10
+}
+
+fn default_auto_create() -> bool {
+    false
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    1
+}
+
+fn default_cleanup_grace_period() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_trash_retention() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+fn default_worktree_base() -> String {
+    "..".to_string()
+}
+
+fn default_remote() -> String {
+    "origin".to_string()
+}
+
+fn default_ignore_patterns() -> Vec<String> {
+    vec!["dependabot/*".to_string(), "renovate/*".to_string()]
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut config = Self {
+            version: default_version(),
+            poll_interval_secs: default_poll_interval(),
+            poll_mode: PollMode::default(),
+            hooks: HooksConfig::default(),
+            on_branch_update: UpdateAction::default(),
+            cleanup: CleanupPolicy::default(),
+            cleanup_grace_period_secs: default_cleanup_grace_period(),
+            trash: false,
+            trash_retention_secs: default_trash_retention(),
+            command_working_dir: None,
+            ignore_patterns: default_ignore_patterns(),
+            include_patterns: Vec::new(),
+            include_authors: Vec::new(),
+            ignore_authors: Vec::new(),
+            auto_create_worktrees: default_auto_create(),
+            max_concurrent_jobs: default_max_concurrent_jobs(),
+            sort_mode: SortMode::default(),
+            group_by_prefix: false,
+            worktree_base_dir: default_worktree_base(),
+            base_branch: None,
+            remote_name: default_remote(),
+            remotes: Vec::new(),
+            inherited: None,
+            sources: BTreeMap::new(),
+            compiled: CompiledFilters::default(),
+        };
+        config.compile_patterns();
+        config
+    }
+}
+
+impl Config {
+    /// Load the config layers that exist on top of the defaults
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        let mut merged = serde_json::to_value(Config::default())?;
+        let mut sources = BTreeMap::new();
+        let mut inherited = None;
+
+        for layer in ConfigLayer::FILES {
+            if layer == ConfigLayer::Local {
+                inherited = Some(merged.clone());
+            }
+            let Some(config_path) = layer.path(repo_root).filter(|path| path.exists()) else {
+                continue;
+            };
+
+            let value = read_layer(layer, &config_path)?;
+            merge_layer(&mut merged, value, layer, "", &mut sources);
+        }
+
+        let mut config: Config =
+            serde_json::from_value(merged).with_context(|| "Failed to merge config files")?;
+        config.inherited = inherited;
+        config.sources = sources;
+        config.compile_patterns();
+        Ok(config)
+    }
+
+    /// Check every config file that exists, without changing any
+    pub fn validate(repo_root: &Path) -> Result<Vec<FileReport>> {
+        let mut reports = Vec::new();
+        for layer in ConfigLayer::FILES {
+            let Some(config_path) = layer.path(repo_root).filter(|path| path.exists()) else {
+                continue;
+            };
+            let content = std::fs::read_to_string(&config_path).with_context(|| {
+                format!("Failed to read config file: {}", config_path.display())
+            })?;
+            reports.push(schema::validate_file(layer, &config_path, &content));
+        }
+        Ok(reports)
+    }
+
+    /// Check whether the repository has a shared or local config file
+    pub fn exists_in(repo_root: &Path) -> bool {
+        [ConfigLayer::Shared, ConfigLayer::Local]
+            .iter()
+            .filter_map(|layer| layer.path(repo_root))
+            .any(|path| path.exists())
+    }
+
+    /// The layer a value was set by, by its path (e.g., "poll_interval_secs" or
+    /// "hooks.post_create")
+    pub fn source(&self, key: &str) -> ConfigLayer {
+        self.sources
+            .get(key)
+            .copied()
+            .unwrap_or(ConfigLayer::Default)
+    }
+
+    /// The layer of every value not left at its default, by path
+    pub fn sources(&self) -> &BTreeMap<String, ConfigLayer> {
+        &self.sources
+    }
+
+    /// Save the local config: every value that differs from the layers below
+    pub fn save(&self, repo_root: &Path) -> Result<()> {
+        let config_path = repo_root.join(CONFIG_FILE_NAME);
+
+        let value = serde_json::to_value(self).with_context(|| "Failed to serialize config")?;
+        let inherited = match &self.inherited {
+            Some(inherited) => inherited.clone(),
+            None => serde_json::to_value(Config::default())?,
+        };
+        let mut local = diff_layer(&value, &inherited).unwrap_or_else(|| Value::Object(Map::new()));
+        // The version tells which format the file is written in
+        if let Value::Object(map) = &mut local {
+            map.insert("version".to_string(), Value::from(self.version));
+        }
+
+        let content =
+            serde_json::to_string_pretty(&local).with_context(|| "Failed to serialize config")?;
+
+        std::fs::write(&config_path, content)
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+
+        Ok(())
+    }
+
+    /// Get the command configured for a lifecycle stage (if any)
+    pub fn hook_command(&self, stage: HookStage) -> Option<&str> {
+        match stage {
+            HookStage::PreCreate => self.hooks.pre_create.as_deref(),
+            HookStage::PostCreate => self.hooks.post_create.as_deref(),
+            HookStage::PreRemove => self.hooks.pre_remove.as_deref(),
+            HookStage::PostRemove => self.hooks.post_remove.as_deref(),
+            HookStage::PostUpdate => self.hooks.post_update.as_deref(),
+        }
+        // An empty command turns off a hook set by another layer
+        .filter(|command| !command.is_empty())
+    }
+
+    /// The layer a stage's hook command was set by
+    pub fn hook_source(&self, stage: HookStage) -> ConfigLayer {
+        self.source(&format!("hooks.{}", stage.name().replace('-', "_")))
+    }
+
+    /// Get how long a hook may run before it is stopped (if limited)
+    pub fn hook_timeout(&self) -> Option<std::time::Duration> {
+        self.hooks
+            .timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// Set the post-create command
+    pub fn set_post_create_command(&mut self, command: Option<String>) {
+        self.hooks.post_create = command;
+    }
+
+    /// All remotes to watch, `remote_name` first
+    pub fn watched_remotes(&self) -> Vec<RemoteConfig> {
+        let main = self
+            .remotes
+            .iter()
+            .find(|r| r.name == self.remote_name)
+            .cloned()
+            .unwrap_or_else(|| RemoteConfig {
+                name: self.remote_name.clone(),
+                ..Default::default()
+            });
+
+        let mut remotes = vec![main];
+        for remote in &self.remotes {
+            if !remotes.iter().any(|r| r.name == remote.name) {
+                remotes.push(remote.clone());
+            }
+        }
+        remotes
+    }
+
+    /// Names of all remotes to watch, `remote_name` first
+    pub fn remote_names(&self) -> Vec<String> {
+        self.watched_remotes().into_iter().map(|r| r.name).collect()
+    }
+
+    /// Local branch name for a branch on a remote
+    ///
+    /// Branches on `remote_name` keep their name, branches on other remotes are
+    /// prefixed with the remote (e.g., "alice/feature/x").
+    pub fn local_branch_name(&self, remote: &str, branch: &str) -> String {
+        if remote == self.remote_name {
+            branch.to_string()
+        } else {
+            format!("{}/{}", remote, branch)
+        }
+    }
+
+    /// The remote a branch comes from and its name there
+    ///
+    /// Taken from the branch's record rather than its local name: "alice/x" may
+    /// be the fork's "x" as well as a branch of `remote_name`. Local-only branches
+    /// belong to `remote_name` under their own name.
+    pub fn upstream(&self, branch: &RemoteBranch) -> Upstream {
+        if branch.is_local {
+            return self.default_upstream(&branch.name);
+        }
+        let name = branch
+            .full_ref
+            .strip_prefix(branch.remote.as_str())
+            .and_then(|rest| rest.strip_prefix('/'))
+            .unwrap_or(&branch.name);
+        Upstream {
+            remote: branch.remote.clone(),
+            branch: name.to_string(),
+        }
+    }
+
+    /// The upstream of a branch gwa has no record of: the same name on `remote_name`
+    pub fn default_upstream(&self, branch: &str) -> Upstream {
+        Upstream {
+            remote: self.remote_name.clone(),
+            branch: branch.to_string(),
+        }
+    }
+
+    /// Get the base branch of a remote, falling back to `base_branch`
+    pub fn base_branch_for(&self, remote: &str) -> Option<&str> {
+        self.remotes
+            .iter()
+            .find(|r| r.name == remote)
+            .and_then(|r| r.base_branch.as_deref())
+            .or(self.base_branch.as_deref())
+    }
+
+    /// Check if a branch is the base branch of the remote it comes from
+    pub fn is_base_branch(&self, branch: &RemoteBranch) -> bool {
+        let upstream = self.upstream(branch);
+        self.base_branch_for(&upstream.remote) == Some(upstream.branch.as_str())
+    }
+
+    /// Compile the branch and author patterns again, after they were changed
+    ///
+    /// Patterns changed without it still match correctly, only slower: they are
+    /// compiled on every check until then.
+    pub fn compile_patterns(&mut self) {
+        self.compiled = CompiledFilters {
+            ignore_patterns: CompiledPatterns::new(&self.ignore_patterns),
+            include_patterns: CompiledPatterns::new(&self.include_patterns),
+            include_authors: CompiledPatterns::new(&self.include_authors),
+            ignore_authors: CompiledPatterns::new(&self.ignore_authors),
+        };
+    }
+
+    /// Check if the include patterns leave out a branch, by its name on the remote
+    fn is_excluded(&self, branch: &str) -> bool {
+        !self.include_patterns.is_empty()
+            && !compiled(&self.compiled.include_patterns, &self.include_patterns).matches(branch)
+    }
+
+    /// Check if a branch should be ignored based on its name or author
+    pub fn should_ignore(&self, branch: &RemoteBranch) -> bool {
+        compiled(&self.compiled.ignore_patterns, &self.ignore_patterns).matches(&branch.name)
+            || self.hiding_filter(branch).is_some()
+    }
+
+    /// The setting other than `ignore_patterns` that hides a branch, if any
+    ///
+    /// Include and author filters only apply to remote branches other than the
+    /// base branch: local branches are your own. Like a remote's own ignore
+    /// patterns, include patterns match the name on the remote (`feature/*` keeps
+    /// `alice/feature/x`).
+    pub fn hiding_filter(&self, branch: &RemoteBranch) -> Option<&'static str> {
+        if branch.is_local || self.is_base_branch(branch) {
+            return None;
+        }
+        let by_author = |compiled_patterns: &CompiledPatterns, patterns: &[String]| {
+            let patterns = compiled(compiled_patterns, patterns);
+            patterns.matches(&branch.author) || patterns.matches(&branch.author_email)
+        };
+        if self.is_excluded(&self.upstream(branch).branch) {
+            Some("include_patterns")
+        } else if !self.include_authors.is_empty()
+            && !by_author(&self.compiled.include_authors, &self.include_authors)
+        {
+            Some("include_authors")
+        } else if by_author(&self.compiled.ignore_authors, &self.ignore_authors) {
+            Some("ignore_authors")
+        } else {
+            None
+        }
+    }
+
+    /// Add a branch to the ignore list (drops a negation that kept it first)
+    pub fn ignore_branch(&mut self, branch: &str) {
+        let negated = format!("!{}", branch);
+        self.ignore_patterns.retain(|p| *p != negated);
+        if !matches_patterns(&self.ignore_patterns, branch) {
+            self.ignore_patterns.push(branch.to_string());
+        }
+        self.compile_patterns();
+    }
+
+    /// Remove a branch from the ignore list (unignore)
+    ///
+    /// Drops the branch's own entry; a branch still matched by a pattern gets a
+    /// negation (`!branch`) instead.
+    pub fn unignore_branch(&mut self, branch: &str) {
+        self.ignore_patterns.retain(|p| p != branch);
+        if matches_patterns(&self.ignore_patterns, branch) {
+            self.ignore_patterns.push(format!("!{}", branch));
+        }
+        self.compile_patterns();
+    }
+
+    /// Get the worktree directory path for a branch
+    pub fn get_worktree_path(&self, repo_root: &Path, branch: &str) -> PathBuf {
+        let sanitized_branch = sanitize_branch_name(branch);
+        repo_root
+            .join(&self.worktree_base_dir)
+            .join(&sanitized_branch)
+    }
+}
+
+/// Read a config file, upgrading it in place if an older gwa wrote it
+///
+/// Unknown keys are logged as warnings; `gwa config validate` reports them with
+/// their position.
+fn read_layer(layer: ConfigLayer, config_path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+
+    // Parse each file on its own, so errors name the file at fault (and the text,
+    // so they have a line and column)
+    let mut value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
+    let version = schema::file_version(&value);
+    if version > CONFIG_VERSION {
+        return Err(eyre!(
+            "{} is written for version {} of the config format, this gwa supports up to {}; please upgrade gwa",
+            config_path.display(),
+            version,
+            CONFIG_VERSION
+        ));
+    }
+    serde_json::from_str::<Config>(&content)
+        .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
+
+    if version < CONFIG_VERSION {
+        let changes = schema::migrate(&mut value, layer);
+        let backup_path = schema::backup_path(config_path, version);
+        let upgraded = serde_json::to_string_pretty(&value)?;
+        match std::fs::copy(config_path, &backup_path)
+            .and_then(|_| std::fs::write(config_path, upgraded))
+        {
+            // A warning, so it shows on the console once
+            Ok(()) => {
+                warn!(
+                    "Upgraded {} to version {} of the config format (previous file: {})",
+                    config_path.display(),
+                    CONFIG_VERSION,
+                    backup_path.display()
+                );
+                for change in changes {
+                    warn!("  {}", change);
+                }
+            }
+            // The upgraded values are still used, the file is tried again next time
+            Err(e) => warn!("Failed to upgrade {}: {}", config_path.display(), e),
+        }
+    }
+
+    for key in schema::unknown_keys(&value) {
+        warn!("Unknown key {} in {} (ignored)", key, config_path.display());
+    }
+
+    Ok(value)
+}
+
+/// Merge a config layer into the values of the layers below, recording which
+/// values it set
+///
+/// Objects (like `hooks`) are merged key by key, other values (lists too)
+/// replaced; `null` unsets the value below, back to its default.
+fn merge_layer(
+    target: &mut Value,
+    layer_value: Value,
+    layer: ConfigLayer,
+    prefix: &str,
+    sources: &mut BTreeMap<String, ConfigLayer>,
+) {
+    let (Value::Object(target), Value::Object(values)) = (target, layer_value) else {
+        return;
+    };
+
+    for (key, value) in values {
+        let path = format!("{}{}", prefix, key);
+        if value.is_null() {
+            target.remove(&key);
+            let nested = format!("{}.", path);
+            sources.retain(|source, _| !source.starts_with(&nested));
+            sources.insert(path, layer);
+            continue;
+        }
+        match target.get_mut(&key) {
+            Some(existing @ Value::Object(_)) if value.is_object() => {
+                merge_layer(existing, value, layer, &format!("{}.", path), sources);
+            }
+            _ => {
+                target.insert(key, value);
+                sources.insert(path, layer);
+            }
+        }
+    }
+}
+
+/// The values of `value` that differ from `base`, or None if there are none
+///
+/// Objects are compared key by key; a value unset (`null`) in `value` but set
+/// in `base` comes out as `null`, which unsets it again when merged.
+fn diff_layer(value: &Value, base: &Value) -> Option<Value> {
+    match (value, base) {
+        (Value::Object(values), Value::Object(base)) => {
+            let diff: Map<String, Value> = values
+                .iter()
+                .filter_map(|(key, value)| {
+                    let diff = match base.get(key) {
+                        Some(base) => diff_layer(value, base)?,
+                        None if value.is_null() => return None,
+                        None => value.clone(),
+                    };
+                    Some((key.clone(), diff))
+                })
+                .collect();
+            (!diff.is_empty()).then_some(Value::Object(diff))
+        }
+        _ if value == base => None,
+        _ => Some(value.clone()),
+    }
+}
+
+/// Check a branch name against glob patterns (or exact names)
+///
+/// A pattern starting with `regex:` is a regular expression, found anywhere in the
+/// name unless anchored with `^`/`$`. A `!` in front negates a pattern. The last
+/// pattern that matches decides, so `["feature/*", "!feature/keep-me"]` matches
+/// every feature branch but one.
+fn matches_patterns(patterns: &[String], branch: &str) -> bool {
+    CompiledPatterns::new(patterns).matches(branch)
+}
+
+/// The compiled form of `patterns`: `compiled` if it is still up to date
+fn compiled<'a>(compiled: &'a CompiledPatterns, patterns: &[String]) -> Cow<'a, CompiledPatterns> {
+    if compiled.is_compiled_from(patterns) {
+        Cow::Borrowed(compiled)
+    } else {
+        Cow::Owned(CompiledPatterns::new(patterns))
+    }
+}
+
+/// Prefix of patterns that are regular expressions
+const REGEX_PREFIX: &str = "regex:";
+
+/// Why a branch or author pattern is invalid (None if it's fine)
+pub fn pattern_error(pattern: &str) -> Option<String> {
+    CompiledPattern::new(pattern).error
+}
+
+/// Patterns compiled once, for matching many names against the same list (see
+/// [`matches_patterns`] for the syntax)
+#[derive(Debug, Clone, Default)]
+pub struct CompiledPatterns {
+    patterns: Vec<CompiledPattern>,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledPattern {
+    source: String,
+    negated: bool,
+    matcher: Matcher,
+    /// Why the pattern is invalid
+    error: Option<String>,
+}
+
+/// A compiled pattern, None if it is invalid
+#[derive(Debug, Clone)]
+enum Matcher {
+    Glob(Option<glob::Pattern>),
+    Regex(Option<regex::Regex>),
+}
+
+impl CompiledPattern {
+    fn new(source: &str) -> Self {
+        let pattern = source.strip_prefix('!').unwrap_or(source);
+        let (matcher, error) = match pattern.strip_prefix(REGEX_PREFIX) {
+            Some(regex) => match regex::Regex::new(regex) {
+                Ok(regex) => (Matcher::Regex(Some(regex)), None),
+                Err(e) => (Matcher::Regex(None), Some(e.to_string())),
+            },
+            None => match glob::Pattern::new(pattern) {
+                Ok(glob) => (Matcher::Glob(Some(glob)), None),
+                Err(e) => (Matcher::Glob(None), Some(e.to_string())),
+            },
+        };
+        Self {
+            source: source.to_string(),
+            negated: pattern.len() < source.len(),
+            matcher,
+            error,
+        }
+    }
+
+    fn matches(&self, branch: &str) -> bool {
+        match &self.matcher {
+            Matcher::Regex(regex) => regex.as_ref().is_some_and(|regex| regex.is_match(branch)),
+            // Also check the exact name (for branch names added via 't' key)
+            Matcher::Glob(glob) => {
+                glob.as_ref().is_some_and(|glob| glob.matches(branch))
+                    || self.source.strip_prefix('!').unwrap_or(&self.source) == branch
+            }
+        }
+    }
+}
+
+impl CompiledPatterns {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns.iter().map(|p| CompiledPattern::new(p)).collect(),
+        }
+    }
+
+    /// The same patterns with the one at `index` replaced by `pattern`, or with
+    /// `pattern` added (only `pattern` is compiled)
+    pub fn with_pattern(&self, index: Option<usize>, pattern: &str) -> Self {
+        let mut patterns = self.patterns.clone();
+        let pattern = CompiledPattern::new(pattern);
+        match index {
+            Some(i) if i < patterns.len() => patterns[i] = pattern,
+            _ => patterns.push(pattern),
+        }
+        Self { patterns }
+    }
+
+    /// Check a name against the patterns (see [`matches_patterns`])
+    pub fn matches(&self, branch: &str) -> bool {
+        self.deciding(branch)
+            .is_some_and(|index| !self.patterns[index].negated)
+    }
+
+    /// Index of the pattern that decides whether a name matches: the last one
+    /// matching it, negated or not
+    pub fn deciding(&self, branch: &str) -> Option<usize> {
+        self.patterns.iter().rposition(|p| p.matches(branch))
+    }
+
+    /// Why the pattern at `index` is invalid (None if it's fine)
+    pub fn error(&self, index: usize) -> Option<&str> {
+        self.patterns.get(index)?.error.as_deref()
+    }
+
+    /// Check whether these are `patterns`, compiled
+    fn is_compiled_from(&self, patterns: &[String]) -> bool {
+        self.patterns.iter().map(|p| &p.source).eq(patterns)
+    }
+}
+
+impl PartialEq for CompiledPatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns
+            .iter()
+            .map(|p| &p.source)
+            .eq(other.patterns.iter().map(|p| &p.source))
+    }
+}
+
+impl Eq for CompiledPatterns {}
+
+/// Sanitize a branch name for use as a directory name
+pub fn sanitize_branch_name(branch: &str) -> String {
+    branch.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_branch_name() {
+        assert_eq!(
+            sanitize_branch_name("feature/my-branch"),
+            "feature-my-branch"
+        );
+        assert_eq!(sanitize_branch_name("fix:bug"), "fix-bug");
+    }
+
+    #[test]
+    fn test_should_ignore_branch() {
+        let mut config = Config::default();
+        config.ignore_patterns.push("feature/*".to_string());
+        config.ignore_patterns.push("main".to_string());
+
+        assert!(config.should_ignore(&remote_branch("origin", "feature/test")));
+        assert!(config.should_ignore(&remote_branch("origin", "main")));
+        assert!(!config.should_ignore(&remote_branch("origin", "develop")));
+    }
+
+    #[test]
+    fn test_unignore_branch() {
+        let mut config = Config::default();
+        config.ignore_patterns.push("feature/*".to_string());
+        config.ignore_patterns.push("main".to_string());
+
+        // Restoring a branch drops its entry, or negates the pattern hiding it
+        config.unignore_branch("main");
+        assert!(!config.should_ignore(&remote_branch("origin", "main")));
+        config.unignore_branch("feature/test");
+        assert!(!config.should_ignore(&remote_branch("origin", "feature/test")));
+        assert_eq!(config.ignore_patterns.last().unwrap(), "!feature/test");
+        config.ignore_branch("feature/test");
+        assert!(config.should_ignore(&remote_branch("origin", "feature/test")));
+        assert!(
+            !config
+                .ignore_patterns
+                .iter()
+                .any(|p| p.contains("feature/test"))
+        );
+    }
+
+    #[test]
+    fn test_include_and_negated_patterns() {
+        let config = Config {
+            base_branch: Some("main".to_string()),
+            include_patterns: vec!["feature/*".to_string(), "regex:^release/\\d+$".to_string()],
+            ignore_patterns: vec!["feature/wip-*".to_string(), "!feature/wip-keep".to_string()],
+            include_authors: vec!["*@team.example".to_string()],
+            ..Default::default()
+        };
+
+        let ignored = |name: &str| {
+            let mut branch = remote_branch("origin", name);
+            branch.author_email = "alice@team.example".to_string();
+            config.should_ignore(&branch)
+        };
+        assert!(!ignored("feature/x"));
+        assert!(!ignored("release/12"));
+        assert!(ignored("release/12-rc"));
+        assert!(ignored("fix/y"));
+        assert!(ignored("feature/wip-a"));
+        assert!(!ignored("feature/wip-keep"));
+        // Base branches are always watched
+        assert!(!ignored("main"));
+
+        let mut branch = RemoteBranch {
+            full_ref: "origin/feature/x".to_string(),
+            name: "feature/x".to_string(),
+            remote: "origin".to_string(),
+            commit: "abc1234".to_string(),
+            commit_time: 0,
+            author: "Alice".to_string(),
+            author_email: "alice@team.example".to_string(),
+            is_local: false,
+        };
+        assert!(!config.should_ignore(&branch));
+        branch.author_email = "mallory@elsewhere.example".to_string();
+        assert!(config.should_ignore(&branch));
+        assert_eq!(config.hiding_filter(&branch), Some("include_authors"));
+        branch.is_local = true;
+        assert!(!config.should_ignore(&branch));
+        assert_eq!(config.hiding_filter(&branch), None);
+
+        let compiled = CompiledPatterns::new(&config.ignore_patterns);
+        assert_eq!(compiled.deciding("feature/wip-keep"), Some(1));
+        assert_eq!(compiled.deciding("feature/x"), None);
+        assert!(compiled.matches("feature/wip-a"));
+        assert!(!compiled.matches("feature/wip-keep"));
+        let edited = compiled.with_pattern(Some(1), "regex:(");
+        assert!(edited.matches("feature/wip-keep"));
+        assert!(edited.error(1).is_some());
+
+        assert!(pattern_error("regex:(").is_some());
+        assert!(pattern_error("!feature/*").is_none());
+    }
+
+    #[test]
+    fn test_include_patterns_on_other_remotes() {
+        let config = Config {
+            include_patterns: vec!["feature/*".to_string()],
+            ignore_patterns: vec!["alice/feature/wip-*".to_string()],
+            remotes: vec![RemoteConfig {
+                name: "alice".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(!config.should_ignore(&remote_branch("alice", "feature/x")));
+        assert!(config.should_ignore(&remote_branch("alice", "fix/y")));
+        assert_eq!(
+            config.hiding_filter(&remote_branch("alice", "fix/y")),
+            Some("include_patterns")
+        );
+        // A branch on `remote_name` named like the fork's isn't the fork's
+        assert!(config.should_ignore(&remote_branch("origin", "alice/feature/x")));
+        // Ignore patterns still match the local name
+        let wip = remote_branch("alice", "feature/wip-a");
+        assert!(config.should_ignore(&wip));
+        assert_eq!(config.hiding_filter(&wip), None);
+    }
+
+    #[test]
+    fn test_include_patterns_skip_local_branches() {
+        let config = Config {
+            include_patterns: vec!["feature/*".to_string()],
+            ignore_patterns: vec!["tmp/*".to_string()],
+            ..Default::default()
+        };
+        let mut branch = RemoteBranch {
+            full_ref: "fix/x".to_string(),
+            name: "fix/x".to_string(),
+            remote: String::new(),
+            commit: "abc1234".to_string(),
+            commit_time: 0,
+            author: "Alice".to_string(),
+            author_email: "alice@example.com".to_string(),
+            is_local: true,
+        };
+
+        assert!(!config.should_ignore(&branch));
+        branch.is_local = false;
+        assert!(config.should_ignore(&branch));
+
+        // Ignore patterns still hide local branches
+        branch.is_local = true;
+        branch.name = "tmp/scratch".to_string();
+        assert!(config.should_ignore(&branch));
+    }
+
+    #[test]
+    fn test_remote_branch_names() {
+        let config = Config {
+            base_branch: Some("main".to_string()),
+            remotes: vec![RemoteConfig {
+                name: "alice".to_string(),
+                base_branch: Some("trunk".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(config.remote_names(), ["origin", "alice"]);
+        assert_eq!(config.local_branch_name("origin", "feature/x"), "feature/x");
+        assert_eq!(
+            config.local_branch_name("alice", "feature/x"),
+            "alice/feature/x"
+        );
+        assert_eq!(
+            config.default_upstream("feature/x").tracking_ref(),
+            "refs/remotes/origin/feature/x"
+        );
+        assert!(config.is_base_branch(&remote_branch("origin", "main")));
+        assert!(config.is_base_branch(&remote_branch("alice", "trunk")));
+        assert!(!config.is_base_branch(&remote_branch("alice", "main")));
+        assert_ne!(
+            config.get_worktree_path(Path::new("/repo"), "feature/x"),
+            config.get_worktree_path(Path::new("/repo"), "alice/feature/x")
+        );
+    }
+
+    #[test]
+    fn test_colliding_branch_names() {
+        let config = Config {
+            base_branch: Some("main".to_string()),
+            remotes: vec![RemoteConfig {
+                name: "alice".to_string(),
+                base_branch: Some("trunk".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // Both show up locally as "alice/trunk"
+        let on_origin = remote_branch("origin", "alice/trunk");
+        let on_fork = remote_branch("alice", "trunk");
+        assert_eq!(on_origin.name, on_fork.name);
+
+        let upstream = config.upstream(&on_origin);
+        assert_eq!(upstream.remote, "origin");
+        assert_eq!(upstream.tracking_ref(), "refs/remotes/origin/alice/trunk");
+        assert!(!config.is_base_branch(&on_origin));
+
+        let upstream = config.upstream(&on_fork);
+        assert_eq!(upstream.remote, "alice");
+        assert_eq!(upstream.tracking_ref(), "refs/remotes/alice/trunk");
+        assert!(config.is_base_branch(&on_fork));
+    }
+
+    /// A branch as the watcher lists it from `remote`
+    fn remote_branch(remote: &str, branch: &str) -> RemoteBranch {
+        RemoteBranch {
+            full_ref: format!("{}/{}", remote, branch),
+            name: Config::default().local_branch_name(remote, branch),
+            remote: remote.to_string(),
+            commit: "abc1234".to_string(),
+            commit_time: 0,
+            author: "Alice".to_string(),
+            author_email: "alice@example.com".to_string(),
+            is_local: false,
+        }
+    }
+
+    #[test]
+    fn test_hook_command() {
+        let mut config = Config::default();
+        assert_eq!(config.hook_command(HookStage::PostCreate), None);
+
+        config.hooks.post_create = Some("make setup".to_string());
+        assert_eq!(
+            config.hook_command(HookStage::PostCreate),
+            Some("make setup")
+        );
+        assert_eq!(config.hook_command(HookStage::PreRemove), None);
+
+        config.hooks.post_create = Some(String::new());
+        assert_eq!(config.hook_command(HookStage::PostCreate), None);
+    }
+
+    #[test]
+    fn test_layers() {
+        let mut merged = serde_json::to_value(Config::default()).unwrap();
+        let mut sources = BTreeMap::new();
+        let shared = serde_json::json!({
+            "poll_interval_secs": 60,
+            "hooks": { "post_create": "npm install", "pre_remove": "make clean" },
+            "ignore_patterns": ["main", "release/*"],
+            "base_branch": "develop"
+        });
+        let local = serde_json::json!({
+            "hooks": { "post_create": "pnpm install" },
+            "ignore_patterns": ["main"]
+        });
+
+        merge_layer(&mut merged, shared, ConfigLayer::Shared, "", &mut sources);
+        let inherited = merged.clone();
+        merge_layer(&mut merged, local, ConfigLayer::Local, "", &mut sources);
+
+        let config: Config = serde_json::from_value(merged.clone()).unwrap();
+        assert_eq!(config.poll_interval_secs, 60);
+        assert_eq!(config.hooks.post_create.as_deref(), Some("pnpm install"));
+        assert_eq!(config.hooks.pre_remove.as_deref(), Some("make clean"));
+        assert_eq!(config.base_branch.as_deref(), Some("develop"));
+        // Lists replace the list below, they aren't merged
+        assert_eq!(config.ignore_patterns, vec!["main"]);
+
+        assert_eq!(sources["poll_interval_secs"], ConfigLayer::Shared);
+        assert_eq!(sources["hooks.post_create"], ConfigLayer::Local);
+        assert_eq!(sources["hooks.pre_remove"], ConfigLayer::Shared);
+        assert_eq!(sources["ignore_patterns"], ConfigLayer::Local);
+
+        // Saving keeps only what the local layer changed, lists as a whole
+        assert_eq!(
+            diff_layer(&merged, &inherited),
+            Some(serde_json::json!({
+                "hooks": { "post_create": "pnpm install" },
+                "ignore_patterns": ["main"]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_null_unsets_layers_below() {
+        let mut merged = serde_json::to_value(Config::default()).unwrap();
+        let mut sources = BTreeMap::new();
+        let shared = serde_json::json!({
+            "hooks": { "post_create": "npm install", "pre_remove": "make clean" },
+            "base_branch": "develop"
+        });
+        let local = serde_json::json!({
+            "hooks": { "pre_remove": null },
+            "base_branch": null
+        });
+
+        merge_layer(&mut merged, shared, ConfigLayer::Shared, "", &mut sources);
+        let inherited = merged.clone();
+        merge_layer(&mut merged, local, ConfigLayer::Local, "", &mut sources);
+
+        let mut config: Config = serde_json::from_value(merged.clone()).unwrap();
+        assert_eq!(config.hooks.post_create.as_deref(), Some("npm install"));
+        assert_eq!(config.hooks.pre_remove, None);
+        assert_eq!(config.base_branch, None);
+        assert_eq!(sources["hooks.pre_remove"], ConfigLayer::Local);
+        assert_eq!(sources["base_branch"], ConfigLayer::Local);
+
+        // Clearing a value in gwa saves a null for it
+        config.hooks.post_create = None;
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            diff_layer(&value, &inherited),
+            Some(serde_json::json!({
+                "hooks": { "post_create": null, "pre_remove": null },
+                "base_branch": null
+            }))
+        );
+    }
+}
@@ -0,0 +1,406 @@
+//! Config file format: versions, migrations and validation
+//!
+//! Every config file records the `version` of the format it was written in.
+//! Files written by an older gwa are upgraded one version at a time when they are
+//! loaded, and the original is kept next to them as `<file>.v<version>.bak`.
+//! Files without a `version` are taken to be written by hand for the current
+//! format.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+use super::{CONFIG_VERSION, Config, ConfigLayer, RemoteConfig, diff_layer, pattern_error};
+
+/// Keys holding lists of branch or author patterns
+const PATTERN_KEYS: [&str; 4] = [
+    "ignore_patterns",
+    "include_patterns",
+    "include_authors",
+    "ignore_authors",
+];
+
+/// The format version a config file was written in
+pub fn file_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(CONFIG_VERSION, |version| {
+            u32::try_from(version).unwrap_or(u32::MAX)
+        })
+}
+
+/// Upgrade the values of a config file to the current format
+///
+/// Returns a description of every change made.
+pub fn migrate(value: &mut Value, layer: ConfigLayer) -> Vec<String> {
+    let version = file_version(value);
+    let mut changes = Vec::new();
+    let Value::Object(map) = value else {
+        return changes;
+    };
+
+    if version < 2 {
+        v1_to_v2(map, layer, &mut changes);
+    }
+
+    map.insert("version".to_string(), Value::from(CONFIG_VERSION));
+    changes
+}
+
+/// Where the original of a migrated file is kept
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// Version 2 layers the config files and keeps the last fetch in the state file
+fn v1_to_v2(map: &mut Map<String, Value>, layer: ConfigLayer, changes: &mut Vec<String>) {
+    if let Some(command) = map.remove("post_create_command").filter(|c| !c.is_null()) {
+        let hooks = map
+            .entry("hooks")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(hooks) = hooks {
+            if hooks.get("post_create").is_none_or(Value::is_null) {
+                hooks.insert("post_create".to_string(), command);
+                changes.push("Moved post_create_command to hooks.post_create".to_string());
+            } else {
+                changes.push(
+                    "Dropped post_create_command, which hooks.post_create overrode".to_string(),
+                );
+            }
+        }
+    }
+
+    if map.remove("last_fetch").is_some() {
+        changes.push("Dropped last_fetch, which is kept in the state file now".to_string());
+    }
+
+    // gwa used to save every option to the local file, which would now override
+    // whatever the shared and global files set
+    if layer == ConfigLayer::Local {
+        let Ok(defaults) = serde_json::to_value(Config::default()) else {
+            return;
+        };
+        let kept = match diff_layer(&Value::Object(map.clone()), &defaults) {
+            Some(Value::Object(kept)) => kept,
+            _ => Map::new(),
+        };
+        let dropped: Vec<&str> = map
+            .keys()
+            .filter(|key| !kept.contains_key(*key) && *key != "version")
+            .map(String::as_str)
+            .collect();
+        if !dropped.is_empty() {
+            changes.push(format!(
+                "Dropped options left at their default: {}",
+                dropped.join(", ")
+            ));
+        }
+        *map = kept;
+    }
+}
+
+/// Paths of the keys the config format doesn't know (e.g., "hooks.post_craete")
+pub fn unknown_keys(value: &Value) -> Vec<String> {
+    let Ok(mut template) = serde_json::to_value(Config::default()) else {
+        return Vec::new();
+    };
+    if let Ok(remote) = serde_json::to_value(RemoteConfig::default()) {
+        template["remotes"] = Value::Array(vec![remote]);
+    }
+
+    let mut unknown = Vec::new();
+    collect_unknown(value, &template, "", &mut unknown);
+    unknown
+}
+
+fn collect_unknown(value: &Value, template: &Value, path: &str, unknown: &mut Vec<String>) {
+    match (value, template) {
+        (Value::Object(values), Value::Object(known)) => {
+            for (key, value) in values {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match known.get(key) {
+                    Some(template) => collect_unknown(value, template, &path, unknown),
+                    None => unknown.push(path),
+                }
+            }
+        }
+        // Every element is checked against the one in the template
+        (Value::Array(values), Value::Array(known)) => {
+            if let Some(template) = known.first() {
+                for (i, value) in values.iter().enumerate() {
+                    collect_unknown(value, template, &format!("{}[{}]", path, i), unknown);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// How bad a problem in a config file is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// gwa can't load the file
+    Error,
+    /// The file loads, but probably not as intended
+    Warning,
+}
+
+impl Severity {
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A problem found in a config file, with its position if known
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    /// A parse or type error reported by serde_json
+    fn from_json_error(e: &serde_json::Error) -> Self {
+        let position = format!(" at line {} column {}", e.line(), e.column());
+        let message = e.to_string();
+        Self {
+            severity: Severity::Error,
+            line: (e.line() > 0).then_some(e.line()),
+            column: (e.line() > 0).then_some(e.column()),
+            message: message
+                .strip_suffix(&position)
+                .unwrap_or(&message)
+                .to_string(),
+        }
+    }
+
+    fn warning(position: Option<(usize, usize)>, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            message,
+        }
+    }
+}
+
+/// The result of checking one config file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub layer: ConfigLayer,
+    pub path: PathBuf,
+    /// Format version of the file (None if it couldn't be parsed)
+    pub version: Option<u32>,
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl FileReport {
+    /// Check whether gwa can load the file
+    pub fn is_valid(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| issue.severity != Severity::Error)
+    }
+}
+
+/// Check a config file without loading or changing it
+pub fn validate_file(layer: ConfigLayer, path: &Path, content: &str) -> FileReport {
+    let mut report = FileReport {
+        layer,
+        path: path.to_path_buf(),
+        version: None,
+        issues: Vec::new(),
+    };
+
+    let mut value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => {
+            report.issues.push(ConfigIssue::from_json_error(&e));
+            return report;
+        }
+    };
+    let version = file_version(&value);
+    report.version = Some(version);
+
+    if version > CONFIG_VERSION {
+        report.issues.push(ConfigIssue {
+            severity: Severity::Error,
+            line: None,
+            column: None,
+            message: format!(
+                "Written for version {} of the config format, this gwa supports up to {}",
+                version, CONFIG_VERSION
+            ),
+        });
+        return report;
+    }
+
+    // Parse the text itself (not the value), so type errors have a position
+    if let Err(e) = serde_json::from_str::<Config>(content) {
+        report.issues.push(ConfigIssue::from_json_error(&e));
+    }
+
+    if version < CONFIG_VERSION {
+        let changes = migrate(&mut value, layer);
+        report.issues.push(ConfigIssue::warning(
+            None,
+            format!(
+                "Version {} of the config format, upgraded to {} the next time gwa loads it{}",
+                version,
+                CONFIG_VERSION,
+                if changes.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", changes.join("; "))
+                }
+            ),
+        ));
+    }
+
+    for key in unknown_keys(&value) {
+        let name = key.rsplit('.').next().unwrap_or(&key);
+        report.issues.push(ConfigIssue::warning(
+            key_position(content, name),
+            format!("Unknown key {} (ignored)", key),
+        ));
+    }
+
+    for (key, pattern) in patterns(&value) {
+        if let Some(error) = pattern_error(pattern) {
+            // Escaped the way it is written in the file
+            let quoted = serde_json::to_string(pattern).unwrap_or_default();
+            report.issues.push(ConfigIssue::warning(
+                content
+                    .find(&quoted)
+                    .map(|offset| position(content, offset)),
+                format!(
+                    "Invalid pattern {} in {} (matches nothing): {}",
+                    quoted, key, error
+                ),
+            ));
+        }
+    }
+
+    report
+}
+
+/// Every branch and author pattern of a config file, with the key it is in
+fn patterns(value: &Value) -> Vec<(String, &str)> {
+    let remotes = value
+        .get("remotes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, remote)| {
+            (
+                format!("remotes[{}].ignore_patterns", i),
+                remote.get("ignore_patterns"),
+            )
+        });
+    let lists = PATTERN_KEYS
+        .iter()
+        .map(|key| (key.to_string(), value.get(*key)))
+        .chain(remotes);
+
+    lists
+        .filter_map(|(key, list)| Some((key, list?.as_array()?)))
+        .flat_map(|(key, list)| {
+            list.iter()
+                .filter_map(Value::as_str)
+                .map(move |pattern| (key.clone(), pattern))
+        })
+        .collect()
+}
+
+/// Line and column (both 1-based) of the first `"key":` in a JSON document
+fn key_position(content: &str, key: &str) -> Option<(usize, usize)> {
+    let quoted = format!("\"{}\"", key);
+    let (offset, _) = content
+        .match_indices(&quoted)
+        .find(|(i, _)| content[i + quoted.len()..].trim_start().starts_with(':'))?;
+    Some(position(content, offset))
+}
+
+/// Line and column (both 1-based) of a byte offset in a document
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_v1() {
+        let mut value = serde_json::json!({
+            "version": 1,
+            "poll_interval_secs": 10,
+            "post_create_command": "npm install",
+            "hooks": { "post_create": null, "pre_remove": null },
+            "ignore_patterns": ["main"],
+            "last_fetch": "2024-01-01T00:00:00Z"
+        });
+
+        let changes = migrate(&mut value, ConfigLayer::Local);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "version": CONFIG_VERSION,
+                "hooks": { "post_create": "npm install" },
+                "ignore_patterns": ["main"]
+            })
+        );
+
+        // Current files are left alone
+        assert!(migrate(&mut value, ConfigLayer::Local).is_empty());
+    }
+
+    #[test]
+    fn test_validate_file() {
+        let content =
+            "{\n  \"poll_interval_secs\": \"10\",\n  \"hooks\": { \"post_craete\": \"make\" }\n}";
+        let report = validate_file(ConfigLayer::Shared, Path::new(".gwa.json"), content);
+
+        assert!(!report.is_valid());
+        let error = &report.issues[0];
+        assert_eq!((error.line, error.column), (Some(2), Some(28)));
+
+        let warning = &report.issues[1];
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(warning.message, "Unknown key hooks.post_craete (ignored)");
+        assert_eq!((warning.line, warning.column), (Some(3), Some(14)));
+
+        let content = "{\n  \"include_patterns\": [\"feature/*\", \"regex:(\"]\n}";
+        let report = validate_file(ConfigLayer::Shared, Path::new(".gwa.json"), content);
+
+        assert!(report.is_valid());
+        let warning = &report.issues[0];
+        assert!(
+            warning
+                .message
+                .starts_with("Invalid pattern \"regex:(\" in include_patterns")
+        );
+        assert_eq!((warning.line, warning.column), (Some(2), Some(37)));
+    }
+}
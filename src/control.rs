@@ -0,0 +1,419 @@
+//! Local control socket
+//!
+//! A running gwa instance (TUI or daemon) listens on a Unix domain socket next to
+//! the config file and speaks newline-delimited JSON-RPC 2.0. Editor integrations
+//! and `gwa ctl` use it to act on the live instance instead of racing it on git
+//! state and the config file.
+//!
+//! Methods:
+//! - `fetch` - start a fetch now
+//! - `queue` `{ "branch": "..." }` - queue a branch for worktree creation
+//! - `cancel_hook` `{ "branch": "..." }` - cancel the running hook for a branch
+//! - `statuses` - same document as `gwa list --format json`
+//! - `subscribe` - stream every `WatcherEvent` as an `event` notification
+//!
+//! Requests without an `id` are notifications: they run, but get no response.
+
+use color_eyre::eyre::{Result, eyre};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::cli::{self, BranchList, Document};
+use crate::config::Config;
+use crate::git::{Repository, WorktreeAgent};
+use crate::ui::BranchStatus;
+use crate::watcher::{Watcher, WatcherEvent};
+
+/// The name of the socket file stored next to the config file
+pub const SOCKET_FILE_NAME: &str = ".gwa.sock";
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32000;
+
+/// A JSON-RPC request
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    /// None for a notification, which gets no response (`"id": null` is a request)
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC error object
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A request received on a connection, with the channel to write the reply to
+struct Incoming {
+    request: Request,
+    out: mpsc::Sender<String>,
+}
+
+/// Everything a request may act on, borrowed from the front-end's state
+pub struct ControlContext<'a> {
+    pub watcher: &'a mut Watcher,
+    pub repo: &'a Repository,
+    pub config: &'a mut Config,
+    pub event_tx: &'a mpsc::Sender<WatcherEvent>,
+}
+
+/// Listener for the control socket
+///
+/// Connections are served on background threads; requests are handed to the
+/// front-end's loop through [`ControlServer::process`] so they run on the same
+/// thread as everything else that touches the watcher.
+pub struct ControlServer {
+    socket_path: PathBuf,
+    incoming_rx: mpsc::Receiver<Incoming>,
+    subscribers: Vec<mpsc::Sender<String>>,
+}
+
+impl ControlServer {
+    /// Bind the socket in the main worktree root
+    ///
+    /// Fails if another instance is already listening. A stale socket left behind
+    /// by a crashed instance is replaced.
+    #[cfg(unix)]
+    pub fn start(main_root: &Path) -> Result<Self> {
+        use std::os::unix::net::{UnixListener, UnixStream};
+        use std::thread;
+        use tracing::{debug, warn};
+
+        let socket_path = main_root.join(SOCKET_FILE_NAME);
+
+        if socket_path.exists() {
+            if UnixStream::connect(&socket_path).is_ok() {
+                return Err(eyre!(
+                    "Another gwa instance is already listening on {}",
+                    socket_path.display()
+                ));
+            }
+            std::fs::remove_file(&socket_path)?;
+        }
+
+        let listener = UnixListener::bind(&socket_path)?;
+        debug!("Control socket listening on {}", socket_path.display());
+
+        let (incoming_tx, incoming_rx) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let incoming_tx = incoming_tx.clone();
+                        thread::spawn(move || serve_connection(stream, incoming_tx));
+                    }
+                    Err(e) => warn!("Control socket accept failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            incoming_rx,
+            subscribers: Vec::new(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_main_root: &Path) -> Result<Self> {
+        Err(eyre!("The control socket is only supported on Unix"))
+    }
+
+    /// Handle all pending requests, returning how many were handled
+    pub fn process(&mut self, ctx: &mut ControlContext) -> usize {
+        let mut handled = 0;
+
+        while let Ok(Incoming { request, out }) = self.incoming_rx.try_recv() {
+            handled += 1;
+
+            let result = if request.method == "subscribe" {
+                self.subscribers.push(out.clone());
+                Ok(json!({ "subscribed": true }))
+            } else {
+                dispatch(&request, ctx)
+            };
+
+            if let Some(id) = request.id {
+                let _ = out.send(response_line(id, result));
+            }
+        }
+
+        handled
+    }
+
+    /// Send an event notification to every subscriber
+    pub fn broadcast(&mut self, event: &WatcherEvent) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+
+        let line = json!({ "jsonrpc": "2.0", "method": "event", "params": event }).to_string();
+        self.subscribers
+            .retain(|subscriber| subscriber.send(line.clone()).is_ok());
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+/// Read requests from a connection and write back replies and notifications
+#[cfg(unix)]
+fn serve_connection(stream: std::os::unix::net::UnixStream, incoming_tx: mpsc::Sender<Incoming>) {
+    use std::io::{BufRead, BufReader, Write};
+    use std::thread;
+
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+
+    // Single writer so replies and notifications never interleave mid-line
+    let (out_tx, out_rx) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for line in out_rx {
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let reader = BufReader::new(stream);
+    for line in reader.lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let incoming = Incoming {
+                    request,
+                    out: out_tx.clone(),
+                };
+                if incoming_tx.send(incoming).is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, e.to_string());
+                let _ = out_tx.send(response_line(Value::Null, Err(error)));
+            }
+        }
+    }
+}
+
+/// Run a request against the front-end's state
+fn dispatch(request: &Request, ctx: &mut ControlContext) -> Result<Value, RpcError> {
+    match request.method.as_str() {
+        "fetch" => {
+            if ctx.watcher.is_fetching() {
+                return Ok(json!({ "started": false }));
+            }
+            ctx.watcher.start_fetch(
+                ctx.repo.root().to_path_buf(),
+                ctx.config.remote_names(),
+                ctx.config.poll_mode,
+                ctx.event_tx.clone(),
+            );
+            Ok(json!({ "started": true }))
+        }
+        "queue" => {
+            let branch = branch_param(&request.params)?;
+            if ctx.watcher.get_branch_by_name(branch).is_none() {
+                return Err(RpcError::new(
+                    REQUEST_FAILED,
+                    format!("Unknown branch '{}'", branch),
+                ));
+            }
+
+            let worktrees = WorktreeAgent::new(ctx.repo).list().unwrap_or_default();
+            let worktree = worktrees
+                .iter()
+                .find(|w| w.branch.as_deref() == Some(branch));
+            if !matches!(
+                ctx.watcher.branch_status(branch, worktree),
+                BranchStatus::Remote | BranchStatus::LocalPrunable
+            ) {
+                return Err(RpcError::new(
+                    REQUEST_FAILED,
+                    format!("Branch '{}' already has a worktree or is queued", branch),
+                ));
+            }
+
+            ctx.watcher
+                .queue_branch(ctx.repo, ctx.config, branch, ctx.event_tx);
+            Ok(json!({ "queued": true }))
+        }
+        "cancel_hook" => {
+            let branch = branch_param(&request.params)?;
+            Ok(json!({ "cancelled": ctx.watcher.cancel_hook(branch) }))
+        }
+        "statuses" => {
+            let worktrees = WorktreeAgent::new(ctx.repo).list().unwrap_or_default();
+            let branches = cli::branch_entries(ctx.watcher, ctx.config, &worktrees, false);
+            serde_json::to_value(Document::new(BranchList { branches }))
+                .map_err(|e| RpcError::new(REQUEST_FAILED, e.to_string()))
+        }
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", method),
+        )),
+    }
+}
+
+/// Extract the required `branch` parameter
+fn branch_param(params: &Value) -> Result<&str, RpcError> {
+    params
+        .get("branch")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing string parameter 'branch'"))
+}
+
+/// Deserialize a member that may be missing as `Some`, even if it is `null`
+fn present<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// Serialize a JSON-RPC response
+fn response_line(id: Value, result: Result<Value, RpcError>) -> String {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+    .to_string()
+}
+
+/// Call a method on the running instance and print the reply
+///
+/// For `subscribe`, keeps printing event notifications until the instance exits.
+#[cfg(unix)]
+pub fn run_client(main_root: &Path, method: &str, params: Value) -> Result<u8> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let socket_path = main_root.join(SOCKET_FILE_NAME);
+    let mut stream = UnixStream::connect(&socket_path).map_err(|e| {
+        eyre!(
+            "No running gwa instance found at {}: {}",
+            socket_path.display(),
+            e
+        )
+    })?;
+
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    writeln!(stream, "{}", request)?;
+
+    let mut lines = BufReader::new(stream).lines();
+    let Some(line) = lines.next() else {
+        return Err(eyre!("Connection closed without a reply"));
+    };
+    let response: Value = serde_json::from_str(&line?)?;
+
+    if let Some(error) = response.get("error") {
+        eprintln!(
+            "{}",
+            error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("Request failed")
+        );
+        return Ok(cli::exit_code::FAILURE);
+    }
+
+    println!("{}", serde_json::to_string_pretty(&response["result"])?);
+
+    if method == "subscribe" {
+        for line in lines.map_while(Result::ok) {
+            println!("{}", line);
+        }
+    }
+
+    Ok(cli::exit_code::SUCCESS)
+}
+
+#[cfg(not(unix))]
+pub fn run_client(_main_root: &Path, _method: &str, _params: Value) -> Result<u8> {
+    Err(eyre!("The control socket is only supported on Unix"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watcher::tests::setup;
+
+    #[test]
+    fn test_response_line() {
+        let ok: Value =
+            serde_json::from_str(&response_line(json!(7), Ok(json!({ "started": true })))).unwrap();
+        assert_eq!(ok["id"], 7);
+        assert_eq!(ok["result"]["started"], true);
+
+        let missing = branch_param(&Value::Null).unwrap_err();
+        let err: Value = serde_json::from_str(&response_line(json!(8), Err(missing))).unwrap();
+        assert_eq!(err["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let (dir, repo) = setup("control", &[]);
+        let (incoming_tx, incoming_rx) = mpsc::channel();
+        let mut server = ControlServer {
+            socket_path: dir.join(SOCKET_FILE_NAME),
+            incoming_rx,
+            subscribers: Vec::new(),
+        };
+        let (out_tx, out_rx) = mpsc::channel();
+        for line in [
+            r#"{"jsonrpc": "2.0", "method": "nope"}"#,
+            r#"{"jsonrpc": "2.0", "method": "subscribe"}"#,
+            r#"{"jsonrpc": "2.0", "id": null, "method": "nope"}"#,
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "nope"}"#,
+        ] {
+            let incoming = Incoming {
+                request: serde_json::from_str(line).unwrap(),
+                out: out_tx.clone(),
+            };
+            incoming_tx.send(incoming).unwrap();
+        }
+
+        let (event_tx, _event_rx) = mpsc::channel();
+        let handled = server.process(&mut ControlContext {
+            watcher: &mut Watcher::new(),
+            repo: &repo,
+            config: &mut Config::default(),
+            event_tx: &event_tx,
+        });
+        assert_eq!(handled, 4);
+        // A subscribing notification still subscribes
+        assert_eq!(server.subscribers.len(), 1);
+
+        let ids: Vec<Value> = out_rx
+            .try_iter()
+            .map(|line| serde_json::from_str::<Value>(&line).unwrap()["id"].clone())
+            .collect();
+        assert_eq!(ids, [Value::Null, json!(3)]);
+
+        drop(server);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
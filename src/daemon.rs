@@ -0,0 +1,316 @@
+//! Headless daemon mode
+//!
+//! Runs the same poll → queue → create → hook pipeline as the TUI, but without a
+//! terminal. Progress is reported through `tracing`, so it ends up on stdout or in
+//! the file passed with `--log-file`.
+
+use color_eyre::eyre::{Result, eyre};
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+use crate::control::{ControlContext, ControlServer};
+use crate::executor::CommandOutput;
+use crate::git::{Repository, short_hash};
+use crate::runtime::RuntimeState;
+use crate::watcher::{Watcher, WatcherEvent};
+
+/// How often the loop checks for hook output and signals
+const TICK: Duration = Duration::from_millis(100);
+
+/// Run the watcher pipeline until SIGTERM/SIGINT
+///
+/// The first signal stops polling and starting queued branches (they are saved for
+/// the next run), then waits for the worktree currently being processed and any
+/// running hooks to finish. A second signal cancels the running hooks (their whole
+/// process groups) and exits.
+pub fn run(repo: Repository, mut config: Config) -> Result<()> {
+    for remote in config.remote_names() {
+        repo.validate_remote(&remote).map_err(|msg| eyre!(msg))?;
+    }
+
+    // Set by every signal and reset by the loop, which counts them
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [SIGTERM, SIGINT] {
+        signal_hook::flag::register(signal, Arc::clone(&shutdown))?;
+    }
+
+    let (event_tx, event_rx) = mpsc::channel();
+    let mut watcher = Watcher::new();
+    watcher.init(&repo, &config)?;
+    watcher.restore(&repo, &config, RuntimeState::load(&repo), &event_tx);
+
+    let mut control = ControlServer::start(repo.main_root())
+        .inspect_err(|e| warn!("Control socket disabled: {}", e))
+        .ok();
+
+    info!(
+        "Watching '{}' in {} every {}s with {} (auto-create: {})",
+        config.remote_names().join("', '"),
+        repo.main_root().display(),
+        config.poll_interval_secs,
+        config.poll_mode.name(),
+        if config.auto_create_worktrees {
+            "on"
+        } else {
+            "off"
+        }
+    );
+
+    let poll_interval = Duration::from_secs(config.poll_interval_secs);
+    let mut last_poll: Option<Instant> = None;
+    let mut phase = Phase::Running;
+
+    loop {
+        if shutdown.swap(false, Ordering::Relaxed) {
+            phase.on_signal(&mut watcher);
+            if phase == Phase::Stopped {
+                break;
+            }
+        }
+
+        watcher.check_running_hooks(&event_tx);
+
+        if let Some(control) = control.as_mut() {
+            control.process(&mut ControlContext {
+                watcher: &mut watcher,
+                repo: &repo,
+                config: &mut config,
+                event_tx: &event_tx,
+            });
+        }
+
+        while let Ok(event) = event_rx.try_recv() {
+            log_event(&event);
+            if let Some(control) = control.as_mut() {
+                control.broadcast(&event);
+            }
+
+            if !phase.handles(&event) {
+                continue;
+            }
+
+            watcher.handle_event(&event, &repo, &config, &event_tx);
+        }
+
+        if phase != Phase::Running {
+            if phase.is_finished(&watcher) {
+                break;
+            }
+        } else if !watcher.is_fetching() && last_poll.is_none_or(|t| t.elapsed() >= poll_interval) {
+            last_poll = Some(Instant::now());
+            watcher.start_fetch(
+                repo.root().to_path_buf(),
+                config.remote_names(),
+                config.poll_mode,
+                event_tx.clone(),
+            );
+        }
+
+        thread::sleep(TICK);
+    }
+
+    watcher.save_state(&repo);
+    info!("Daemon stopped");
+
+    Ok(())
+}
+
+/// How far the daemon got with shutting down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Polling and working through the queue
+    Running,
+    /// Signalled once: waiting for the current worktree and running hooks
+    Draining,
+    /// Signalled twice: the running hooks were cancelled
+    Stopped,
+}
+
+impl Phase {
+    /// Move on after a SIGTERM/SIGINT
+    ///
+    /// The first stops the queue (its branches are saved for the next run), the
+    /// second cancels the running hooks and waits until their process groups are
+    /// gone.
+    fn on_signal(&mut self, watcher: &mut Watcher) {
+        if *self != Phase::Running {
+            let stopped = watcher.stop_hooks();
+            if !stopped.is_empty() {
+                warn!("Cancelled the hooks of: {}", stopped.join(", "));
+            }
+            *self = Phase::Stopped;
+            return;
+        }
+
+        *self = Phase::Draining;
+        let queued = watcher.stop_queue();
+        if !queued.is_empty() {
+            info!(
+                "Leaving queued branches to the next run: {}",
+                queued.join(", ")
+            );
+        }
+        if watcher.is_processing() || watcher.has_running_hooks() {
+            info!(
+                "Shutting down after the current worktree and hooks finish (signal again to cancel the hooks and exit)"
+            );
+        }
+    }
+
+    /// Check whether the watcher should handle an event
+    ///
+    /// Fetch results are dropped while shutting down, so no new branches are
+    /// picked up.
+    fn handles(&self, event: &WatcherEvent) -> bool {
+        *self == Phase::Running
+            || !matches!(
+                event,
+                WatcherEvent::FetchCompleted(..) | WatcherEvent::FetchFailed(..)
+            )
+    }
+
+    /// Check whether the daemon can exit
+    fn is_finished(&self, watcher: &Watcher) -> bool {
+        match self {
+            Phase::Running => false,
+            Phase::Draining => !watcher.is_processing() && !watcher.has_running_hooks(),
+            Phase::Stopped => true,
+        }
+    }
+}
+
+/// Report a watcher event through tracing
+fn log_event(event: &WatcherEvent) {
+    match event {
+        WatcherEvent::FetchStarted(remote) => debug!("Fetch of {} started", remote),
+        WatcherEvent::FetchCompleted(remote, output) => match output {
+            Some(msg) => debug!("Fetch of {} completed: {}", remote, msg),
+            None => debug!("Fetch of {} completed", remote),
+        },
+        WatcherEvent::FetchFailed(remote, msg) => {
+            warn!("Fetch of {} failed: {}", remote, msg.trim())
+        }
+        WatcherEvent::NewBranchesFound(branches) => {
+            info!("New branches found: {}", branches.join(", "))
+        }
+        WatcherEvent::BranchUpdated { branch, old, new } => {
+            info!(
+                "Branch {} updated: {} -> {}",
+                branch,
+                short_hash(old),
+                short_hash(new)
+            )
+        }
+        WatcherEvent::BranchDeleted(branch) => info!("Branch {} deleted on remote", branch),
+        WatcherEvent::WorktreeCreating(branch) => info!("Creating worktree for {}", branch),
+        WatcherEvent::WorktreeCreated(branch, path) => {
+            info!("Worktree created for {} at {}", branch, path.display())
+        }
+        WatcherEvent::WorktreeCreateFailed(branch, msg) => {
+            error!("Worktree creation failed for {}: {}", branch, msg)
+        }
+        WatcherEvent::WorktreeRemoved(branch, path) => {
+            info!("Worktree removed for {} at {}", branch, path.display())
+        }
+        WatcherEvent::WorktreeRemoveFailed(branch, msg) => {
+            error!("Worktree removal failed for {}: {}", branch, msg)
+        }
+        WatcherEvent::HookStarted(branch, stage) => info!("{} hook started for {}", stage, branch),
+        WatcherEvent::HookOutput(branch, output) => match output {
+            CommandOutput::Stdout(line) | CommandOutput::Stderr(line) => {
+                info!("[{}] {}", branch, line)
+            }
+            _ => {}
+        },
+        WatcherEvent::HookCompleted(branch, stage, code) => {
+            if *code == 0 {
+                info!("{} hook completed for {}", stage, branch);
+            } else {
+                warn!("{} hook failed for {} (exit code: {})", stage, branch, code);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HookStage;
+    use crate::watcher::tests::{setup, start};
+    use std::fs;
+
+    #[test]
+    fn test_drain_then_stop() {
+        let (dir, repo) = setup("daemon", &["feature/x", "feature/y"]);
+        let mut config = Config::default();
+        config.hooks.post_create = Some("sleep 30".to_string());
+        config.max_concurrent_jobs = 1;
+        let (event_tx, _event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        watcher.queue_branch(&repo, &config, "feature/y", &event_tx);
+        assert!(watcher.has_running_hook("feature/x"));
+
+        let mut phase = Phase::Running;
+        let fetched = WatcherEvent::FetchCompleted("origin".to_string(), None);
+        let failed = WatcherEvent::FetchFailed("origin".to_string(), "offline".to_string());
+        let hook_done =
+            WatcherEvent::HookCompleted("feature/x".to_string(), HookStage::PostCreate, 0);
+        assert!(phase.handles(&fetched));
+        assert!(!phase.is_finished(&watcher));
+
+        // The first signal stops the queue but lets the hook run
+        phase.on_signal(&mut watcher);
+        assert_eq!(phase, Phase::Draining);
+        assert!(watcher.is_pending("feature/y"));
+        watcher.try_process_next(&repo, &config, &event_tx);
+        assert!(!watcher.is_current("feature/y"));
+        assert!(watcher.has_running_hook("feature/x"));
+        assert!(!phase.handles(&fetched));
+        assert!(!phase.handles(&failed));
+        assert!(phase.handles(&hook_done));
+        assert!(!phase.is_finished(&watcher));
+
+        // The second one cancels it
+        phase.on_signal(&mut watcher);
+        assert_eq!(phase, Phase::Stopped);
+        assert!(!watcher.has_running_hooks());
+        assert!(phase.is_finished(&watcher));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_drain_finishes_with_the_hooks() {
+        let (dir, repo) = setup("daemon-drain", &["feature/x"]);
+        let mut config = Config::default();
+        config.hooks.post_create = Some("sleep 0.2".to_string());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+
+        let mut phase = Phase::Running;
+        phase.on_signal(&mut watcher);
+        let started = Instant::now();
+        while !phase.is_finished(&watcher) {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            watcher.check_running_hooks(&event_tx);
+            while let Ok(event) = event_rx.try_recv() {
+                if phase.handles(&event) {
+                    watcher.handle_event(&event, &repo, &config, &event_tx);
+                }
+            }
+            thread::sleep(TICK);
+        }
+        assert_eq!(phase, Phase::Draining);
+        assert!(!watcher.is_processing());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
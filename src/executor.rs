@@ -3,14 +3,25 @@
 //! Runs configured commands after a worktree is created (e.g., npm install)
 
 use color_eyre::eyre::{Context, Result};
+use serde::Serialize;
 use std::path::Path;
-use std::process::{Command, Stdio};
-use std::sync::mpsc;
+use std::process::{Child, ChildStderr, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
 use std::thread;
-use tracing::{debug, error, info};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// How often a running command is checked for exit, cancellation or timeout
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a cancelled command's process group gets to exit after SIGTERM
+/// before it is killed
+const KILL_GRACE: Duration = Duration::from_secs(3);
 
 /// Output from a running command
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum CommandOutput {
     /// Standard output line
     Stdout(String),
@@ -26,8 +37,57 @@ pub enum CommandOutput {
 pub struct RunningCommand {
     /// Receiver for command output
     pub output_rx: mpsc::Receiver<CommandOutput>,
+    /// Set once the command was asked to stop
+    cancelled: Arc<AtomicBool>,
     /// Handle to the background thread
-    _handle: thread::JoinHandle<()>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl RunningCommand {
+    /// Stop the command and everything it spawned; it reports `Exit` once the
+    /// shell is gone
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Wait until the command is done with, including the SIGKILL that follows a
+    /// cancel for whatever in its process group ignored the SIGTERM
+    ///
+    /// Call before exiting gwa, which would otherwise leave those behind.
+    pub fn wait(self) {
+        let _ = self.handle.join();
+    }
+}
+
+/// Signal a command's process group (SIGTERM, or SIGKILL with `force`)
+///
+/// Commands run in their own process group, so this also reaches whatever the
+/// shell started (e.g., the node processes behind `npm install`), even after the
+/// shell itself exited.
+#[cfg(unix)]
+fn terminate(child: &mut Child, force: bool) {
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    // SAFETY: killpg has no memory safety preconditions
+    unsafe {
+        libc::killpg(child.id() as libc::pid_t, signal);
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut Child, _force: bool) {
+    let _ = child.kill();
+}
+
+/// Check if anything is left in a command's process group
+#[cfg(unix)]
+fn group_alive(child: &Child) -> bool {
+    // SAFETY: signal 0 only checks that the group exists
+    unsafe { libc::killpg(child.id() as libc::pid_t, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+fn group_alive(_child: &Child) -> bool {
+    false
 }
 
 /// Execute a command in a worktree directory
@@ -62,18 +122,62 @@ impl CommandExecutor {
     }
 
     /// Run a command asynchronously with streaming output
-    pub fn run_async(command: String, working_dir: &Path) -> Result<RunningCommand> {
+    ///
+    /// `env` is added on top of the inherited environment. With a `timeout`, the
+    /// command is stopped like [`RunningCommand::cancel`] once it runs too long.
+    pub fn run_async(
+        command: String,
+        working_dir: &Path,
+        env: Vec<(String, String)>,
+        timeout: Option<Duration>,
+    ) -> Result<RunningCommand> {
         info!(
             "Starting async command: {} in {}",
             command,
             working_dir.display()
         );
 
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", &command]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &command]);
+            cmd
+        };
+        cmd.current_dir(working_dir)
+            .envs(env)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Own process group, so cancelling reaches the whole tree; outside the
+        // terminal's foreground group, reading stdin would stop the command
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0).stdin(Stdio::null());
+        }
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to spawn command: {}", command))?;
+
         let (tx, rx) = mpsc::channel();
-        let working_dir = working_dir.to_path_buf();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let cancelled = Arc::new(AtomicBool::new(false));
 
+        let thread_cancelled = Arc::clone(&cancelled);
         let handle = thread::spawn(move || {
-            let result = Self::run_command_with_output(&command, &working_dir, tx.clone());
+            let result = Self::stream_output(
+                child,
+                stdout,
+                stderr,
+                tx.clone(),
+                &thread_cancelled,
+                timeout,
+            );
 
             if let Err(e) = result {
                 let _ = tx.send(CommandOutput::Error(e.to_string()));
@@ -82,37 +186,23 @@ impl CommandExecutor {
 
         Ok(RunningCommand {
             output_rx: rx,
-            _handle: handle,
+            cancelled,
+            handle,
         })
     }
 
-    /// Internal helper to run command with output streaming
-    fn run_command_with_output(
-        command: &str,
-        working_dir: &Path,
+    /// Internal helper to stream a spawned command's output until it exits
+    fn stream_output(
+        mut child: Child,
+        stdout: Option<ChildStdout>,
+        stderr: Option<ChildStderr>,
         tx: mpsc::Sender<CommandOutput>,
+        cancelled: &AtomicBool,
+        timeout: Option<Duration>,
     ) -> Result<()> {
         use std::io::{BufRead, BufReader};
 
-        let mut child = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .args(["/C", command])
-                .current_dir(working_dir)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-        } else {
-            Command::new("sh")
-                .args(["-c", command])
-                .current_dir(working_dir)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-        }
-        .with_context(|| format!("Failed to spawn command: {}", command))?;
-
         // Read stdout in a thread
-        let stdout = child.stdout.take();
         let tx_stdout = tx.clone();
         let stdout_handle = thread::spawn(move || {
             if let Some(stdout) = stdout {
@@ -126,7 +216,6 @@ impl CommandExecutor {
         });
 
         // Read stderr in a thread
-        let stderr = child.stderr.take();
         let tx_stderr = tx.clone();
         let stderr_handle = thread::spawn(move || {
             if let Some(stderr) = stderr {
@@ -139,12 +228,52 @@ impl CommandExecutor {
             }
         });
 
-        // Wait for the command to complete
-        let status = child.wait().with_context(|| "Failed to wait for command")?;
+        // Wait for the command to complete, stopping it on timeout and escalating
+        // to SIGKILL if it ignores SIGTERM
+        let started = Instant::now();
+        let mut stopping_since: Option<Instant> = None;
+        let status = loop {
+            if let Some(status) = child
+                .try_wait()
+                .with_context(|| "Failed to wait for command")?
+            {
+                break status;
+            }
 
-        // Wait for output threads
-        let _ = stdout_handle.join();
-        let _ = stderr_handle.join();
+            match stopping_since {
+                None if cancelled.load(Ordering::Relaxed) => {
+                    warn!("Cancelled command");
+                    let _ = tx.send(CommandOutput::Stderr("Cancelled".to_string()));
+                    terminate(&mut child, false);
+                    stopping_since = Some(Instant::now());
+                }
+                None if timeout.is_some_and(|t| started.elapsed() >= t) => {
+                    let secs = timeout.unwrap_or_default().as_secs();
+                    warn!("Command timed out after {}s", secs);
+                    let _ = tx.send(CommandOutput::Stderr(format!("Timed out after {}s", secs)));
+                    cancelled.store(true, Ordering::Relaxed);
+                    terminate(&mut child, false);
+                    stopping_since = Some(Instant::now());
+                }
+                Some(since) if since.elapsed() >= KILL_GRACE => {
+                    terminate(&mut child, true);
+                }
+                _ => {}
+            }
+            thread::sleep(WAIT_INTERVAL);
+        };
+
+        // Wait for output threads, unless the command was stopped: something that
+        // escaped the process group may keep the pipes open indefinitely
+        if !cancelled.load(Ordering::Relaxed) {
+            let _ = stdout_handle.join();
+            let _ = stderr_handle.join();
+        } else if stopping_since.is_none() {
+            // Cancelled just as the shell exited: the rest of the group still goes
+            let _ = tx.send(CommandOutput::Stderr("Cancelled".to_string()));
+            terminate(&mut child, false);
+            stopping_since = Some(Instant::now());
+        }
 
         // Send exit status
         let exit_code = status.code().unwrap_or(-1);
@@ -154,6 +283,18 @@ impl CommandExecutor {
             error!("Command failed with exit code: {}", exit_code);
         }
 
+        // Whatever in the group ignored the SIGTERM outlives the shell: kill it once
+        // the grace period is over
+        if let Some(since) = stopping_since {
+            while since.elapsed() < KILL_GRACE && group_alive(&child) {
+                thread::sleep(WAIT_INTERVAL);
+            }
+            // With the group gone its id may belong to another one already
+            if group_alive(&child) {
+                terminate(&mut child, true);
+            }
+        }
+
         Ok(())
     }
 }
@@ -237,3 +378,52 @@ impl CommandLog {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_process_group() {
+        let pid_file =
+            std::env::temp_dir().join(format!("gwa-executor-test-{}.pid", std::process::id()));
+        let _ = std::fs::remove_file(&pid_file);
+
+        // The grandchild ignores SIGTERM and outlives the shell
+        let started = Instant::now();
+        let running = CommandExecutor::run_async(
+            format!(
+                "sh -c 'trap \"\" TERM; echo $$ > {}; exec sleep 30' & sleep 30",
+                pid_file.display()
+            ),
+            &std::env::temp_dir(),
+            Vec::new(),
+            Some(Duration::from_millis(200)),
+        )
+        .unwrap();
+
+        let exit = running.output_rx.iter().find_map(|output| match output {
+            CommandOutput::Exit(code) => Some(code),
+            _ => None,
+        });
+
+        assert_eq!(exit, Some(-1));
+        assert!(started.elapsed() < KILL_GRACE);
+
+        running.wait();
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let _ = std::fs::remove_file(&pid_file);
+        // Gone, or a zombie waiting to be reaped
+        let state = Command::new("ps")
+            .args(["-o", "stat=", "-p", pid.trim()])
+            .output()
+            .unwrap();
+        let state = String::from_utf8_lossy(&state.stdout);
+        assert!(
+            state.trim().is_empty() || state.starts_with('Z'),
+            "{}",
+            state
+        );
+    }
+}
@@ -1,12 +1,14 @@
 //! Repository discovery and remote operations using git CLI
 
 use color_eyre::eyre::{Context, Result, eyre};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use tracing::{debug, warn};
 
 /// Information about a branch (local or remote)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct RemoteBranch {
     /// Full reference for git commands (e.g., "origin/main" or "feature/my-branch" for local)
     pub full_ref: String,
@@ -14,18 +16,82 @@ pub struct RemoteBranch {
     pub name: String,
     /// Remote name (e.g., "origin") or empty for local branches
     pub remote: String,
-    /// Commit hash the branch points to
+    /// Full hash of the commit the branch points to (abbreviated in JSON output)
+    #[serde(serialize_with = "serialize_short_hash")]
     pub commit: String,
+    /// Committer date of that commit (Unix timestamp)
+    pub commit_time: i64,
+    /// Author of that commit
+    pub author: String,
+    /// Email address of that author
+    pub author_email: String,
     /// Whether this is a local branch
     pub is_local: bool,
 }
 
+/// `for-each-ref` format parsed by `parse_branch_line` (tab-separated, as author
+/// names contain spaces)
+const BRANCH_FORMAT: &str =
+    "--format=%(refname)%09%(objectname)%09%(committerdate:unix)%09%(authorname)%09%(authoremail)";
+
+/// Abbreviate a commit hash for display
+///
+/// Only for showing: git lengthens its own abbreviations as a repository grows,
+/// so hashes are compared and stored in full.
+pub fn short_hash(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+fn serialize_short_hash<S: serde::Serializer>(
+    commit: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(short_hash(commit))
+}
+
+/// A line of `BRANCH_FORMAT` output: ref name, commit, commit time, author, email
+fn parse_branch_line(line: &str) -> Option<(&str, &str, i64, &str, &str)> {
+    let mut fields = line.split('\t');
+    let refname = fields.next()?;
+    let commit = fields.next()?;
+    let commit_time = fields.next().and_then(|t| t.parse().ok()).unwrap_or(0);
+    let author = fields.next().unwrap_or_default();
+    let author_email = fields.next().unwrap_or_default().trim_matches(['<', '>']);
+    Some((refname, commit, commit_time, author, author_email))
+}
+
+/// A commit as shown in the branch details
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitSummary {
+    /// Abbreviated commit hash
+    pub hash: String,
+    pub author: String,
+    /// Author date (YYYY-MM-DD)
+    pub date: String,
+    pub subject: String,
+}
+
+/// What a branch contains compared to its base branch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BranchDetails {
+    /// Most recent commits of the branch, newest first
+    pub commits: Vec<CommitSummary>,
+    /// The base branch the branch is compared with (e.g., "origin/main")
+    pub base: Option<String>,
+    /// Abbreviated merge-base with the base branch
+    pub merge_base: Option<String>,
+    /// `git diff --stat` of the branch since the merge-base
+    pub diff_stat: Vec<String>,
+}
+
 /// Wrapper around a git repository (uses git CLI)
 pub struct Repository {
     /// Current worktree root (where we're running from)
     root: PathBuf,
     /// Main worktree root (where .git directory and config live)
     main_root: PathBuf,
+    /// The .git directory shared by all worktrees
+    common_dir: PathBuf,
 }
 
 impl Repository {
@@ -54,17 +120,26 @@ impl Repository {
 
         // Get the main worktree root (where .git directory lives)
         // Use git-common-dir to find the shared .git directory
-        let main_root = Self::find_main_worktree(&root)?;
+        let common_dir = Self::find_common_dir(&root)?;
+        let main_root = match &common_dir {
+            Some(common_dir) => Self::find_main_worktree(&root, common_dir),
+            // Fallback to current root
+            None => root.clone(),
+        };
+        let common_dir = common_dir.unwrap_or_else(|| main_root.join(".git"));
 
         debug!("Discovered git repository at: {}", root.display());
         debug!("Main worktree at: {}", main_root.display());
 
-        Ok(Self { root, main_root })
+        Ok(Self {
+            root,
+            main_root,
+            common_dir,
+        })
     }
 
-    /// Find the main worktree directory (where .git folder and config live)
-    fn find_main_worktree(worktree_root: &Path) -> Result<PathBuf> {
-        // Get the common git directory
+    /// Find the .git directory shared by all worktrees (None if git can't tell)
+    fn find_common_dir(worktree_root: &Path) -> Result<Option<PathBuf>> {
         let output = Command::new("git")
             .args(["rev-parse", "--git-common-dir"])
             .current_dir(worktree_root)
@@ -74,17 +149,19 @@ impl Repository {
             .with_context(|| "Failed to get git common dir")?;
 
         if !output.status.success() {
-            // Fallback to current root
-            return Ok(worktree_root.to_path_buf());
+            return Ok(None);
         }
 
         let git_common_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        let git_common_dir = if Path::new(&git_common_dir).is_absolute() {
+        Ok(Some(if Path::new(&git_common_dir).is_absolute() {
             PathBuf::from(&git_common_dir)
         } else {
             worktree_root.join(&git_common_dir)
-        };
+        }))
+    }
 
+    /// Find the main worktree directory (where .git folder and config live)
+    fn find_main_worktree(worktree_root: &Path, git_common_dir: &Path) -> PathBuf {
         // The main worktree is the parent of the .git directory
         // git-common-dir returns the .git directory itself
         if let Some(parent) = git_common_dir.parent() {
@@ -95,22 +172,22 @@ impl Repository {
                     .map(|n| n == ".git")
                     .unwrap_or(false)
             {
-                return Ok(parent.to_path_buf());
+                return parent.to_path_buf();
             }
         }
 
         // If git-common-dir doesn't end with .git, it might be inside .git/worktrees/
         // In that case, go up to find the actual .git directory
-        let mut current = git_common_dir.as_path();
+        let mut current = git_common_dir;
         while let Some(parent) = current.parent() {
             if current.file_name().map(|n| n == ".git").unwrap_or(false) {
-                return Ok(parent.to_path_buf());
+                return parent.to_path_buf();
             }
             current = parent;
         }
 
         // Fallback
-        Ok(worktree_root.to_path_buf())
+        worktree_root.to_path_buf()
     }
 
     /// Get the current worktree root path
@@ -123,6 +200,11 @@ impl Repository {
         &self.main_root
     }
 
+    /// Get the .git directory shared by all worktrees
+    pub fn common_dir(&self) -> &Path {
+        &self.common_dir
+    }
+
     /// Check if a remote exists
     pub fn remote_exists(&self, remote_name: &str) -> bool {
         Command::new("git")
@@ -174,7 +256,7 @@ impl Repository {
         let output = Command::new("git")
             .args([
                 "for-each-ref",
-                "--format=%(refname:short) %(objectname:short)",
+                BRANCH_FORMAT,
                 &format!("refs/remotes/{}", remote_name),
             ])
             .current_dir(&self.root)
@@ -194,13 +276,15 @@ impl Repository {
         let mut branches = Vec::new();
 
         for line in stdout.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let full_ref = parts[0];
-                let commit = parts[1];
+            if let Some((refname, commit, commit_time, author, author_email)) =
+                parse_branch_line(line)
+            {
+                // Shortened by hand: git's short form turns into "remotes/origin/x"
+                // once a local branch named "origin/x" exists
+                let full_ref = refname.strip_prefix("refs/remotes/").unwrap_or(refname);
 
-                // Skip HEAD (can appear as "origin/HEAD" or just "origin" in short format)
-                if full_ref.ends_with("/HEAD") || full_ref == remote_name {
+                // Skip HEAD
+                if full_ref.ends_with("/HEAD") {
                     continue;
                 }
 
@@ -215,6 +299,9 @@ impl Repository {
                     name,
                     remote: remote_name.to_string(),
                     commit: commit.to_string(),
+                    commit_time,
+                    author: author.to_string(),
+                    author_email: author_email.to_string(),
                     is_local: false,
                 });
             }
@@ -231,11 +318,7 @@ impl Repository {
     /// Get all local branches
     pub fn get_local_branches(&self) -> Result<Vec<RemoteBranch>> {
         let output = Command::new("git")
-            .args([
-                "for-each-ref",
-                "--format=%(refname:short) %(objectname:short)",
-                "refs/heads",
-            ])
+            .args(["for-each-ref", BRANCH_FORMAT, "refs/heads"])
             .current_dir(&self.root)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -252,16 +335,23 @@ impl Repository {
         let mut branches = Vec::new();
 
         for line in stdout.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let name = parts[0].to_string();
-                let commit = parts[1];
+            if let Some((refname, commit, commit_time, author, author_email)) =
+                parse_branch_line(line)
+            {
+                // Shortened by hand, see `get_remote_branches`
+                let name = refname
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(refname)
+                    .to_string();
 
                 branches.push(RemoteBranch {
                     full_ref: name.clone(), // For local branches, full_ref is same as name
                     name,
                     remote: String::new(),
                     commit: commit.to_string(),
+                    commit_time,
+                    author: author.to_string(),
+                    author_email: author_email.to_string(),
                     is_local: true,
                 });
             }
@@ -271,6 +361,101 @@ impl Repository {
         Ok(branches)
     }
 
+    /// Get the local branches whose upstream branch was deleted on the remote
+    ///
+    /// Returns `(branch, upstream ref)` pairs, e.g. `("feature/x",
+    /// "refs/remotes/origin/feature/x")`. Relies on git marking the upstream as
+    /// `[gone]` once a fetch pruned it.
+    pub fn get_gone_branches(&self) -> Result<Vec<(String, String)>> {
+        let output = Command::new("git")
+            .args([
+                "for-each-ref",
+                "--format=%(refname) %(upstream) %(upstream:track)",
+                "refs/heads",
+            ])
+            .current_dir(&self.root)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| "Failed to run git for-each-ref for upstreams")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("git for-each-ref failed: {}", stderr);
+            return Ok(Vec::new());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let gone = stdout
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let refname = parts.next()?;
+                let upstream = parts.next()?;
+                (parts.next() == Some("[gone]")).then(|| {
+                    let name = refname.strip_prefix("refs/heads/").unwrap_or(refname);
+                    (name.to_string(), upstream.to_string())
+                })
+            })
+            .collect();
+
+        Ok(gone)
+    }
+
+    /// Get the remote and the branch on it that a local branch tracks, e.g.
+    /// `("alice", "feature/x")` for the local branch "alice/feature/x" of a fork
+    pub fn get_tracked_branch(&self, branch: &str) -> Option<(String, String)> {
+        let output = Command::new("git")
+            .args([
+                "for-each-ref",
+                "--format=%(upstream:remotename)%09%(upstream:remoteref)",
+                &format!("refs/heads/{}", branch),
+            ])
+            .current_dir(&self.root)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (remote, remote_ref) = stdout.lines().next()?.split_once('\t')?;
+        let name = remote_ref.strip_prefix("refs/heads/")?;
+        (!remote.is_empty()).then(|| (remote.to_string(), name.to_string()))
+    }
+
+    /// Check whether `commit` is reachable from `target` (i.e. merged into it)
+    pub fn is_merged(&self, commit: &str, target: &str) -> bool {
+        Command::new("git")
+            .args(["merge-base", "--is-ancestor", commit, target])
+            .current_dir(&self.root)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Resolve a revision to a full commit hash
+    pub fn resolve_commit(&self, rev: &str) -> Option<String> {
+        let output = Command::new("git")
+            .args([
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", rev),
+            ])
+            .current_dir(&self.root)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     /// Get the default branch for a remote (e.g., origin/HEAD -> origin/main)
     pub fn get_default_branch(&self, remote_name: &str) -> Option<String> {
         // Try to get the remote's HEAD reference
@@ -337,10 +522,277 @@ impl Repository {
     }
 }
 
+/// More changed branches than this are fetched with a plain `git fetch --prune`
+/// instead of one refspec each (e.g., on the first poll)
+const MAX_NARROW_FETCH_REFS: usize = 64;
+
+/// Run `git fetch --prune` for a remote (blocking)
+///
+/// Returns any output messages (warnings, info, etc.) on success, or the error
+/// output if the fetch really failed. Warnings on stderr that cause a non-zero
+/// exit status are not treated as failures.
+pub fn fetch_remote(repo_root: &Path, remote_name: &str) -> Result<Option<String>, String> {
+    run_fetch(repo_root, &["fetch", "--prune", remote_name])
+}
+
+/// Fetch only the branches of a remote that changed (blocking)
+///
+/// Asks the server for its branch heads with `git ls-remote --heads`, which is
+/// cheap even for huge repositories, and compares them with the remote-tracking
+/// refs. Nothing is fetched if they match; otherwise only the new and moved
+/// branches are fetched and the refs of deleted branches are removed. Returns
+/// the same as [`fetch_remote`].
+pub fn fetch_changed_refs(repo_root: &Path, remote_name: &str) -> Result<Option<String>, String> {
+    let remote_heads = ls_remote_heads(repo_root, remote_name)?;
+    let tracking_refs = tracking_ref_commits(repo_root, remote_name)?;
+
+    let mut changed: Vec<&String> = remote_heads
+        .iter()
+        .filter(|(branch, commit)| tracking_refs.get(*branch) != Some(*commit))
+        .map(|(branch, _)| branch)
+        .collect();
+    changed.sort();
+
+    let deleted: Vec<&String> = tracking_refs
+        .keys()
+        .filter(|branch| !remote_heads.contains_key(*branch))
+        .collect();
+
+    if changed.is_empty() && deleted.is_empty() {
+        debug!("No branch changed on {}", remote_name);
+        return Ok(None);
+    }
+
+    if changed.len() > MAX_NARROW_FETCH_REFS {
+        return fetch_remote(repo_root, remote_name);
+    }
+
+    let mut messages = Vec::new();
+
+    if !changed.is_empty() {
+        let refspecs: Vec<String> = changed
+            .iter()
+            .map(|branch| format!("+refs/heads/{0}:refs/remotes/{1}/{0}", branch, remote_name))
+            .collect();
+
+        let mut args = vec!["fetch", remote_name];
+        args.extend(refspecs.iter().map(String::as_str));
+        if let Some(output) = run_fetch(repo_root, &args)? {
+            messages.push(output);
+        }
+    }
+
+    // `--prune` only looks at the refspecs given, so drop deleted branches by hand
+    for branch in deleted {
+        let tracking_ref = format!("refs/remotes/{}/{}", remote_name, branch);
+        let status = Command::new("git")
+            .args(["update-ref", "-d", &tracking_ref])
+            .current_dir(repo_root)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| e.to_string())?;
+
+        if status.success() {
+            messages.push(format!("- [deleted] {}/{}", remote_name, branch));
+        } else {
+            warn!("Failed to delete {}", tracking_ref);
+        }
+    }
+
+    if messages.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(messages.join("\n")))
+    }
+}
+
+/// List the branch heads of a remote with their commits, as the server sees them
+fn ls_remote_heads(repo_root: &Path, remote_name: &str) -> Result<HashMap<String, String>, String> {
+    let output = Command::new("git")
+        .args(["ls-remote", "--heads", remote_name])
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let heads = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (commit, refname) = line.split_once('\t')?;
+            let branch = refname.strip_prefix("refs/heads/")?;
+            Some((branch.to_string(), commit.to_string()))
+        })
+        .collect();
+
+    Ok(heads)
+}
+
+/// Get the full commits of a remote's remote-tracking refs, by branch name
+fn tracking_ref_commits(
+    repo_root: &Path,
+    remote_name: &str,
+) -> Result<HashMap<String, String>, String> {
+    let prefix = format!("refs/remotes/{}/", remote_name);
+    let output = Command::new("git")
+        .args(["for-each-ref", "--format=%(objectname) %(refname)", &prefix])
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let refs = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (commit, refname) = line.split_once(' ')?;
+            let branch = refname.strip_prefix(&prefix)?;
+            (branch != "HEAD").then(|| (branch.to_string(), commit.to_string()))
+        })
+        .collect();
+
+    Ok(refs)
+}
+
+/// Collect the recent commits of `rev` and its changes versus `base_ref`
+///
+/// A free function so it can run on a background thread. Without a base (or
+/// without a common ancestor) only the commits are filled in.
+pub fn branch_details(
+    repo_root: &Path,
+    rev: &str,
+    base_ref: Option<&str>,
+    commit_count: usize,
+) -> Result<BranchDetails, String> {
+    let log = run_git(
+        repo_root,
+        &[
+            "log",
+            &format!("--max-count={}", commit_count),
+            "--date=short",
+            "--format=%h%x1f%an%x1f%ad%x1f%s",
+            rev,
+            "--",
+        ],
+    )?;
+    let commits = log
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            Some(CommitSummary {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect();
+
+    let mut details = BranchDetails {
+        commits,
+        ..Default::default()
+    };
+
+    let Some(base_ref) = base_ref else {
+        return Ok(details);
+    };
+    details.base = Some(
+        base_ref
+            .strip_prefix("refs/remotes/")
+            .unwrap_or(base_ref)
+            .to_string(),
+    );
+
+    // Unrelated histories have no merge-base; that's not an error here
+    let Ok(merge_base) = run_git(repo_root, &["merge-base", base_ref, rev]) else {
+        return Ok(details);
+    };
+    let merge_base = merge_base.trim().to_string();
+
+    let stat = run_git(repo_root, &["diff", "--stat=100", &merge_base, rev, "--"])?;
+    details.diff_stat = stat.lines().map(str::to_string).collect();
+    details.merge_base = Some(merge_base.chars().take(7).collect());
+
+    Ok(details)
+}
+
+/// Run a git command and return its stdout, or stderr as the error
+fn run_git(repo_root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Run a `git fetch` command, telling real failures apart from warnings
+fn run_fetch(repo_root: &Path, args: &[&str]) -> Result<Option<String>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Collect any output messages (warnings, info, etc.)
+    let mut messages = Vec::new();
+    for line in stderr.lines().chain(stdout.lines()) {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            messages.push(trimmed.to_string());
+        }
+    }
+
+    if !output.status.success() {
+        // Check if it's a real error or just a warning
+        let is_real_error = stderr.lines().any(|line| {
+            let line = line.trim().to_lowercase();
+            !line.is_empty()
+                && !line.starts_with("warning")
+                && !line.contains("post-quantum")
+                && !line.starts_with("hint:")
+                && !line.starts_with("from ")
+        });
+
+        if is_real_error {
+            return Err(stderr.to_string());
+        }
+    }
+
+    if messages.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(messages.join("\n")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::watcher::tests::{commit, git, setup};
     use std::env;
+    use std::fs;
 
     #[test]
     fn test_discover_repo() {
@@ -350,4 +802,33 @@ mod tests {
             assert!(repo.root().exists());
         }
     }
+
+    #[test]
+    fn test_branch_details() {
+        let (dir, repo) = setup("details", &[]);
+        let root = repo.root();
+        git(root, &["checkout", "-q", "-b", "topic"]);
+        fs::write(root.join("notes.txt"), "one\ntwo\n").unwrap();
+        git(root, &["add", "notes.txt"]);
+        commit(root, "Add notes");
+        commit(root, "Tidy up");
+
+        let details = branch_details(root, "topic", Some("refs/remotes/origin/main"), 2).unwrap();
+        let subjects: Vec<&str> = details.commits.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(subjects, ["Tidy up", "Add notes"]);
+        assert_eq!(details.commits[0].author, "t");
+        assert_eq!(details.base.as_deref(), Some("origin/main"));
+        let main = git(root, &["rev-parse", "--short=7", "origin/main"]);
+        assert_eq!(details.merge_base, Some(main));
+        assert!(details.diff_stat[0].contains("notes.txt"));
+        assert!(details.diff_stat.last().unwrap().contains("1 file changed"));
+
+        // Without a base only the commits are known
+        let details = branch_details(root, "topic", None, 1).unwrap();
+        assert_eq!(details.commits.len(), 1);
+        assert_eq!(details.base, None);
+        assert!(details.diff_stat.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
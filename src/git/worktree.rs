@@ -1,6 +1,8 @@
 //! Git worktree operations
 
 use color_eyre::eyre::{Context, Result, eyre};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use tracing::{debug, info, warn};
@@ -8,7 +10,7 @@ use tracing::{debug, info, warn};
 use super::Repository;
 
 /// Information about an existing worktree
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WorktreeInfo {
     /// Path to the worktree
     pub path: PathBuf,
@@ -24,6 +26,28 @@ pub struct WorktreeInfo {
     pub is_prunable: bool,
 }
 
+/// Local state of a worktree: uncommitted changes, upstream divergence, stashes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WorktreeStatus {
+    /// Staged, unstaged and conflicted files
+    pub changed: usize,
+    /// Untracked files
+    pub untracked: usize,
+    /// Commits not on the upstream branch
+    pub ahead: usize,
+    /// Upstream commits not in the worktree
+    pub behind: usize,
+    /// Stash entries made on this branch
+    pub stashes: usize,
+}
+
+impl WorktreeStatus {
+    /// Whether the worktree holds work that exists nowhere else
+    pub fn has_unsaved_work(&self) -> bool {
+        self.changed > 0 || self.untracked > 0 || self.ahead > 0 || self.stashes > 0
+    }
+}
+
 /// Manager for git worktree operations
 pub struct WorktreeAgent<'a> {
     repo: &'a Repository,
@@ -123,8 +147,10 @@ impl<'a> WorktreeAgent<'a> {
     }
 
     /// Create a new worktree for a branch
+    /// If the branch doesn't exist locally yet, it is created tracking `upstream`
+    /// (e.g., "refs/remotes/origin/feature/x").
     /// Returns (success, output_messages) where output_messages contains git output for logging
-    pub fn create(&self, branch: &str, path: &Path, remote: &str) -> Result<Vec<String>> {
+    pub fn create(&self, branch: &str, path: &Path, upstream: &str) -> Result<Vec<String>> {
         info!(
             "Creating worktree for branch '{}' at: {}",
             branch,
@@ -162,12 +188,11 @@ impl<'a> WorktreeAgent<'a> {
                 .with_context(|| "Failed to run git worktree add")?
         } else {
             // Branch doesn't exist locally - create it tracking remote
-            let remote_ref = format!("{}/{}", remote, branch);
             log_messages.push(format!(
                 "$ git worktree add --track -b {} {} {}",
                 branch,
                 path.display(),
-                remote_ref
+                upstream
             ));
 
             Command::new("git")
@@ -178,7 +203,7 @@ impl<'a> WorktreeAgent<'a> {
                     "-b",
                     branch,
                     path.to_string_lossy().as_ref(),
-                    &remote_ref,
+                    upstream,
                 ])
                 .current_dir(self.repo.root())
                 .stdout(Stdio::piped())
@@ -321,6 +346,103 @@ impl<'a> WorktreeAgent<'a> {
         Ok(log_messages)
     }
 
+    /// Check whether a worktree has uncommitted changes (untracked files included)
+    pub fn is_dirty(&self, path: &Path) -> Result<bool> {
+        let output = Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(path)
+            .output()
+            .with_context(|| "Failed to run git status")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(eyre!("git status failed: {}", stderr.trim()));
+        }
+
+        Ok(!output.stdout.is_empty())
+    }
+
+    /// Count the commits of a worktree's HEAD that `upstream` lacks and vice versa
+    ///
+    /// Returns `(ahead, behind)`.
+    pub fn ahead_behind(&self, path: &Path, upstream: &str) -> Result<(usize, usize)> {
+        let range = format!("HEAD...{}", upstream);
+        let output = Command::new("git")
+            .args(["rev-list", "--left-right", "--count", &range])
+            .current_dir(path)
+            .output()
+            .with_context(|| "Failed to run git rev-list")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(eyre!("git rev-list failed: {}", stderr.trim()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut counts = stdout.split_whitespace().map(str::parse::<usize>);
+        match (counts.next(), counts.next()) {
+            (Some(Ok(ahead)), Some(Ok(behind))) => Ok((ahead, behind)),
+            _ => Err(eyre!("Unexpected git rev-list output: {}", stdout.trim())),
+        }
+    }
+
+    /// Count the commits reachable from `rev` that are on no remote
+    pub fn unpushed_commits(&self, rev: &str) -> Result<usize> {
+        let output = Command::new("git")
+            .args(["rev-list", "--count", rev, "--not", "--remotes"])
+            .current_dir(self.repo.root())
+            .output()
+            .with_context(|| "Failed to run git rev-list")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(eyre!("git rev-list failed: {}", stderr.trim()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .trim()
+            .parse()
+            .map_err(|_| eyre!("Unexpected git rev-list output: {}", stdout.trim()))
+    }
+
+    /// Fast-forward the branch checked out in a worktree to `upstream`
+    ///
+    /// Runs `git merge --ff-only`, so a worktree with local commits is left alone.
+    /// Returns the git output for logging.
+    pub fn fast_forward(&self, path: &Path, upstream: &str) -> Result<Vec<String>> {
+        info!(
+            "Fast-forwarding worktree at {} to {}",
+            path.display(),
+            upstream
+        );
+
+        let mut log_messages = vec![format!("$ git merge --ff-only {}", upstream)];
+
+        let output = Command::new("git")
+            .args(["merge", "--ff-only", upstream])
+            .current_dir(path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| "Failed to run git merge")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        for line in stdout.lines().chain(stderr.lines()) {
+            if !line.trim().is_empty() {
+                log_messages.push(line.to_string());
+            }
+        }
+
+        if !output.status.success() {
+            return Err(eyre!("git merge --ff-only failed: {}", stderr.trim()));
+        }
+
+        Ok(log_messages)
+    }
+
     /// Remove a worktree
     pub fn remove(&self, path: &Path, force: bool) -> Result<()> {
         info!("Removing worktree at: {}", path.display());
@@ -351,6 +473,31 @@ impl<'a> WorktreeAgent<'a> {
         Ok(())
     }
 
+    /// Delete a local branch
+    ///
+    /// Uses `git branch -D`: callers check that the branch is merged into its base
+    /// branch themselves, which `-d` doesn't know about.
+    pub fn delete_branch(&self, branch: &str) -> Result<()> {
+        info!("Deleting local branch: {}", branch);
+
+        let output = Command::new("git")
+            .args(["branch", "-D", branch])
+            .current_dir(self.repo.root())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| "Failed to run git branch -D")?;
+
+        if !output.status.success() {
+            return Err(eyre!(
+                "git branch -D failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Prune stale worktree references
     #[allow(dead_code)]
     pub fn prune(&self) -> Result<()> {
@@ -392,6 +539,80 @@ impl<'a> WorktreeAgent<'a> {
     }
 }
 
+/// Get the status of a worktree (without stashes, see `stash_counts`)
+///
+/// A free function so it can run on a background thread.
+pub fn worktree_status(path: &Path) -> Result<WorktreeStatus> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch"])
+        .current_dir(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| "Failed to run git status")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("git status failed: {}", stderr.trim()));
+    }
+
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Count the stash entries of each branch
+///
+/// Stashes are shared by all worktrees; the branch is taken from the stash
+/// message ("WIP on <branch>: ..." or "On <branch>: ...").
+pub fn stash_counts(repo_root: &Path) -> HashMap<String, usize> {
+    let Ok(output) = Command::new("git")
+        .args(["stash", "list", "--format=%gs"])
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return HashMap::new();
+    };
+
+    let mut counts = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some(rest) = line
+            .strip_prefix("WIP on ")
+            .or_else(|| line.strip_prefix("On "))
+        else {
+            continue;
+        };
+        if let Some((branch, _)) = rest.split_once(':')
+            && branch != "(no branch)"
+        {
+            *counts.entry(branch.to_string()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Parse `git status --porcelain=v2 --branch` output
+fn parse_status(output: &str) -> WorktreeStatus {
+    let mut status = WorktreeStatus::default();
+
+    for line in output.lines() {
+        if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // "+<ahead> -<behind>"
+            let mut counts = ab
+                .split_whitespace()
+                .map(|n| n.trim_start_matches(['+', '-']).parse().unwrap_or(0));
+            status.ahead = counts.next().unwrap_or(0);
+            status.behind = counts.next().unwrap_or(0);
+        } else if line.starts_with("1 ") || line.starts_with("2 ") || line.starts_with("u ") {
+            status.changed += 1;
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        }
+    }
+
+    status
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,4 +638,24 @@ branch refs/heads/feature/my-feature
             assert_eq!(worktrees[1].branch.as_deref(), Some("feature/my-feature"));
         }
     }
+
+    #[test]
+    fn test_parse_status() {
+        let output = "# branch.oid abc123
+# branch.head feature/x
+# branch.upstream origin/feature/x
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 abc abc src/main.rs
+2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs
+? notes.txt
+";
+
+        let status = parse_status(output);
+
+        assert_eq!(status.changed, 2);
+        assert_eq!(status.untracked, 1);
+        assert_eq!(status.ahead, 2);
+        assert_eq!(status.behind, 1);
+        assert!(status.has_unsaved_work());
+    }
 }
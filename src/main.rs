@@ -5,15 +5,21 @@
 //! after each worktree is created.
 
 mod app;
+mod cli;
 mod config;
+mod control;
+mod daemon;
 mod executor;
 mod git;
+mod runtime;
+mod trash;
 mod ui;
 mod watcher;
 
 use clap::Parser;
 use color_eyre::eyre::Result;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
 /// Git Worktree Manager - Manage git worktrees from remote branches
@@ -48,9 +54,17 @@ struct Args {
     /// Print the current configuration
     #[arg(long)]
     show_config: bool,
+
+    /// Output format for listing commands (list, status, --show-config)
+    #[arg(long, value_enum, global = true, default_value_t = cli::OutputFormat::Text)]
+    format: cli::OutputFormat,
+
+    /// Run a command non-interactively instead of starting the TUI
+    #[command(subcommand)]
+    command: Option<cli::Command>,
 }
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     // Parse command line arguments
     let args = Args::parse();
 
@@ -64,7 +78,8 @@ fn main() -> Result<()> {
         .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory"));
 
     // Check if we're running in TUI mode
-    let is_tui_mode = !args.show_config
+    let is_tui_mode = args.command.is_none()
+        && !args.show_config
         && !args.init
         && args.set_command.is_none()
         && args.set_poll_interval.is_none()
@@ -90,6 +105,29 @@ fn main() -> Result<()> {
                 .init();
         }
         // If not debug, don't initialize any logging - TUI handles status display
+    } else if let Some(cli::Command::Daemon { log_file }) = &args.command {
+        // The daemon reports progress through logging (stdout or --log-file)
+        let filter = if args.debug {
+            EnvFilter::new("debug")
+        } else {
+            EnvFilter::new("info")
+        };
+        let layer = tracing_subscriber::fmt::layer().with_target(false);
+        if let Some(path) = log_file {
+            let log_file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(layer.with_ansi(false).with_writer(log_file))
+                .init();
+        } else {
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(layer)
+                .init();
+        }
     } else {
         // For CLI commands, log to console
         let filter = if args.debug {
@@ -104,16 +142,20 @@ fn main() -> Result<()> {
     }
 
     // Handle non-TUI commands
+    if let Some(ref command) = args.command {
+        return cli::run(command, &repo_path, args.format);
+    }
+
     if args.show_config {
-        return show_config(&repo_path);
+        return show_config(&repo_path, args.format).map(|_| ExitCode::SUCCESS);
     }
 
     if args.set_command.is_some() || args.set_poll_interval.is_some() || args.auto_create {
-        return update_config(&repo_path, &args);
+        return update_config(&repo_path, &args).map(|_| ExitCode::SUCCESS);
     }
 
     if args.init {
-        return init_config(&repo_path);
+        return init_config(&repo_path).map(|_| ExitCode::SUCCESS);
     }
 
     // Run the TUI application
@@ -143,7 +185,7 @@ fn main() -> Result<()> {
         println!();
     }
 
-    result.map(|_| ())
+    result.map(|_| ExitCode::SUCCESS)
 }
 
 /// Show a startup error in a TUI dialog
@@ -268,25 +310,129 @@ fn show_startup_error(mut terminal: ratatui::DefaultTerminal, error_msg: &str) {
 }
 
 /// Show the current configuration
-fn show_config(repo_path: &Path) -> Result<()> {
+fn show_config(repo_path: &Path, format: cli::OutputFormat) -> Result<()> {
     let repo = git::Repository::discover(repo_path)?;
     let config = config::Config::load(repo.main_root())?;
 
+    if format == cli::OutputFormat::Json {
+        let worktrees = git::WorktreeAgent::new(&repo).list().unwrap_or_default();
+        return cli::print_json(cli::ConfigReport {
+            config: &config,
+            sources: config.sources(),
+            worktrees: &worktrees,
+        });
+    }
+
+    // Values not left at their default are marked with the layer that set them
+    let from = |layer: config::ConfigLayer| match layer {
+        config::ConfigLayer::Default => String::new(),
+        layer => format!("  [{}]", layer.name()),
+    };
+    let from_key = |key: &str| from(config.source(key));
+
     println!("Git Worktree Manager Configuration");
     println!("================================");
     println!();
-    println!("Remote: {}", config.remote_name);
-    println!("Poll interval: {}s", config.poll_interval_secs);
-    println!("Auto-create: {}", config.auto_create_worktrees);
-    println!("Worktree base: {}", config.worktree_base_dir);
+    println!("Config files:");
+    for layer in config::ConfigLayer::FILES {
+        if let Some(path) = layer.path(repo.main_root()) {
+            let state = if path.exists() { "" } else { " (not found)" };
+            println!("  {:<7} {}{}", layer.name(), path.display(), state);
+        }
+    }
+    println!();
     println!(
-        "Post-create command: {}",
-        config.post_create_command.as_deref().unwrap_or("(none)")
+        "Remote: {}{}",
+        config.remote_names().join(", "),
+        from(config.source("remote_name").max(config.source("remotes")))
     );
-    println!();
-    println!("Ignore patterns ({}):", config.ignore_patterns.len());
-    for pattern in &config.ignore_patterns {
-        println!("  * {}", pattern);
+    println!(
+        "Poll interval: {}s{}",
+        config.poll_interval_secs,
+        from_key("poll_interval_secs")
+    );
+    println!(
+        "Poll mode: {}{}",
+        config.poll_mode.name(),
+        from_key("poll_mode")
+    );
+    println!(
+        "On branch update: {}{}",
+        config.on_branch_update.name(),
+        from_key("on_branch_update")
+    );
+    println!(
+        "Cleanup: {} (grace period: {}s){}",
+        config.cleanup.name(),
+        config.cleanup_grace_period_secs,
+        from(
+            config
+                .source("cleanup")
+                .max(config.source("cleanup_grace_period_secs"))
+        )
+    );
+    println!(
+        "Trash: {} (retention: {}s){}",
+        config.trash,
+        config.trash_retention_secs,
+        from(
+            config
+                .source("trash")
+                .max(config.source("trash_retention_secs"))
+        )
+    );
+    println!(
+        "Auto-create: {}{}",
+        config.auto_create_worktrees,
+        from_key("auto_create_worktrees")
+    );
+    println!(
+        "Concurrent jobs: {}{}",
+        config.max_concurrent_jobs,
+        from_key("max_concurrent_jobs")
+    );
+    println!(
+        "Worktree base: {}{}",
+        config.worktree_base_dir,
+        from_key("worktree_base_dir")
+    );
+    println!("Hooks:");
+    for stage in config::HookStage::ALL {
+        println!(
+            "  {:<12} {}{}",
+            stage.name(),
+            config.hook_command(stage).unwrap_or("(none)"),
+            from(config.hook_source(stage))
+        );
+    }
+    let filters = [
+        (
+            "Ignore patterns",
+            "ignore_patterns",
+            &config.ignore_patterns,
+        ),
+        (
+            "Include patterns",
+            "include_patterns",
+            &config.include_patterns,
+        ),
+        (
+            "Include authors",
+            "include_authors",
+            &config.include_authors,
+        ),
+        ("Ignore authors", "ignore_authors", &config.ignore_authors),
+    ];
+    for (title, key, patterns) in filters {
+        // Empty filters other than the ignore patterns are off
+        if patterns.is_empty() && key != "ignore_patterns" {
+            continue;
+        }
+        println!();
+        println!("{} ({}):{}", title, patterns.len(), from_key(key));
+        for pattern in patterns {
+            println!("  * {}", pattern);
+        }
     }
 
     // Show actual worktrees from git
@@ -310,7 +456,7 @@ fn update_config(repo_path: &Path, args: &Args) -> Result<()> {
     let mut config = config::Config::load(repo.main_root())?;
 
     if let Some(ref cmd) = args.set_command {
-        config.post_create_command = Some(cmd.clone());
+        config.set_post_create_command(Some(cmd.clone()));
         println!("Set post-create command: {}", cmd);
     }
 
@@ -366,14 +512,16 @@ fn init_config(repo_path: &Path) -> Result<()> {
     // Get post-create command
     print!(
         "Post-create command (e.g., 'npm install') [{}]: ",
-        config.post_create_command.as_deref().unwrap_or("")
+        config
+            .hook_command(config::HookStage::PostCreate)
+            .unwrap_or("")
     );
     io::stdout().flush()?;
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
     let input = input.trim();
     if !input.is_empty() {
-        config.post_create_command = Some(input.to_string());
+        config.set_post_create_command(Some(input.to_string()));
     }
 
     // Get auto-create setting
@@ -0,0 +1,219 @@
+//! Runtime state of gwa
+//!
+//! What gwa learns while it runs (when it last fetched, the remote branch commits
+//! it has seen, its worktree queue, orphaned worktrees and recent hook runs) is
+//! kept in
+//! `<git common dir>/gwa-state.json` instead of the config files, so those only
+//! change when a setting does and can be committed.
+//!
+//! Several instances (a TUI and a daemon, say) share the file. Every update
+//! re-reads it under a lock and changes only what the instance knows about, and
+//! the file is replaced atomically. The queue belongs to one instance at a time:
+//! the one holding the queue lock restores and saves it, so two instances never
+//! both create the worktrees it holds.
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::path::Path;
+use tracing::warn;
+
+use crate::config::HookStage;
+use crate::git::Repository;
+
+/// The name of the state file inside the git common dir
+pub const STATE_FILE_NAME: &str = "gwa-state.json";
+
+/// Lock file serializing updates of the state file
+const LOCK_FILE_NAME: &str = "gwa-state.lock";
+
+/// Lock file held by the instance owning the queue for as long as it runs
+const QUEUE_LOCK_FILE_NAME: &str = "gwa-queue.lock";
+
+/// How many hook runs the history keeps
+const MAX_HOOK_HISTORY: usize = 100;
+
+/// A finished hook command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookRun {
+    pub branch: String,
+    pub stage: HookStage,
+    pub exit_code: i32,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// State kept between runs of gwa
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeState {
+    /// Last time we fetched from remote
+    #[serde(default)]
+    pub last_fetch: Option<DateTime<Utc>>,
+
+    /// Commit of every known remote branch, by local branch name
+    #[serde(default)]
+    pub branch_commits: BTreeMap<String, String>,
+
+    /// Branches queued for worktree creation, in order
+    #[serde(default)]
+    pub pending_branches: Vec<String>,
+
+    /// Base refs of the queued branches that are created as new branches
+    #[serde(default)]
+    pub new_branch_bases: BTreeMap<String, String>,
+
+    /// When the remote branch of each orphaned worktree was first found deleted
+    #[serde(default)]
+    pub orphaned_since: BTreeMap<String, DateTime<Utc>>,
+
+    /// Most recent hook runs, oldest first
+    #[serde(default)]
+    pub hook_history: Vec<HookRun>,
+}
+
+impl RuntimeState {
+    /// Load the state of a repository (empty if there is none yet)
+    pub fn load(repo: &Repository) -> Self {
+        Self::load_file(&repo.common_dir().join(STATE_FILE_NAME))
+    }
+
+    /// Change the state as saved by all instances
+    ///
+    /// Reads the current file and writes it back changed by `change`, holding a
+    /// lock so no other instance saves in between.
+    pub fn update(repo: &Repository, change: impl FnOnce(&mut Self)) -> Result<()> {
+        Self::update_in(repo.common_dir(), change)
+    }
+
+    /// Claim the queue for this instance, unless another one owns it
+    ///
+    /// The claim lasts until the returned file is dropped or the process exits.
+    pub fn claim_queue(repo: &Repository) -> Option<File> {
+        Self::claim_queue_in(repo.common_dir())
+    }
+
+    fn load_file(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable state file {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    fn update_in(dir: &Path, change: impl FnOnce(&mut Self)) -> Result<()> {
+        let lock_path = dir.join(LOCK_FILE_NAME);
+        let lock = open_lock_file(&lock_path)?;
+        lock.lock()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+        let path = dir.join(STATE_FILE_NAME);
+        let mut state = Self::load_file(&path);
+        change(&mut state);
+        state.save(&path)
+        // Dropping the lock file releases the lock
+    }
+
+    fn claim_queue_in(dir: &Path) -> Option<File> {
+        let lock = open_lock_file(&dir.join(QUEUE_LOCK_FILE_NAME))
+            .inspect_err(|e| warn!("{}", e))
+            .ok()?;
+        lock.try_lock().is_ok().then_some(lock)
+    }
+
+    /// Save the state, replacing the file atomically
+    fn save(&self, path: &Path) -> Result<()> {
+        // A file per process, so concurrent saves don't write into each other's
+        let tmp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+
+        let content =
+            serde_json::to_string_pretty(self).with_context(|| "Failed to serialize state")?;
+        fs::write(&tmp_path, content)
+            .with_context(|| format!("Failed to write state file: {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, path).with_context(|| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("Failed to replace state file: {}", path.display())
+        })?;
+
+        Ok(())
+    }
+
+    /// Add a finished hook run, dropping the oldest beyond the history limit
+    pub fn record_hook(&mut self, run: HookRun) {
+        self.hook_history.push(run);
+        let excess = self.hook_history.len().saturating_sub(MAX_HOOK_HISTORY);
+        self.hook_history.drain(..excess);
+    }
+}
+
+fn open_lock_file(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open lock file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_history_limit() {
+        let mut state = RuntimeState::default();
+        for i in 0..MAX_HOOK_HISTORY + 5 {
+            state.record_hook(HookRun {
+                branch: format!("branch-{}", i),
+                stage: HookStage::PostCreate,
+                exit_code: 0,
+                finished_at: Utc::now(),
+            });
+        }
+
+        assert_eq!(state.hook_history.len(), MAX_HOOK_HISTORY);
+        assert_eq!(state.hook_history[0].branch, "branch-5");
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        let dir = std::env::temp_dir().join(format!("gwa-state-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    RuntimeState::update_in(&dir, |state| {
+                        state.record_hook(HookRun {
+                            branch: format!("branch-{}", i),
+                            stage: HookStage::PostCreate,
+                            exit_code: 0,
+                            finished_at: Utc::now(),
+                        });
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // No instance wrote over another's runs
+        let state = RuntimeState::load_file(&dir.join(STATE_FILE_NAME));
+        assert_eq!(state.hook_history.len(), 8);
+
+        // One owner of the queue at a time
+        let claim = RuntimeState::claim_queue_in(&dir);
+        assert!(claim.is_some());
+        assert!(RuntimeState::claim_queue_in(&dir).is_none());
+        drop(claim);
+        assert!(RuntimeState::claim_queue_in(&dir).is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
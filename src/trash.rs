@@ -0,0 +1,404 @@
+//! Trash for removed worktrees
+//!
+//! With `"trash": true`, removing a worktree moves its directory into
+//! `<git common dir>/gwa-trash/<id>/` instead of deleting it. Git's
+//! administrative directory of the worktree (`worktrees/<name>`, holding its
+//! index and HEAD) moves along, so git forgets the worktree and its branch can
+//! be checked out elsewhere, while a restore brings back staged and uncommitted
+//! changes as they were. A `refs/gwa-trash/<id>` ref keeps the recorded HEAD
+//! from being garbage collected, even if the branch is deleted, and
+//! `refs/gwa-trash/<id>-index` does the same for the staged files, which only
+//! the index git no longer sees refers to.
+//!
+//! Entries older than `trash_retention_secs` are purged.
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Context, Result, eyre};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
+
+use crate::git::{Repository, WorktreeAgent, short_hash};
+
+/// The name of the trash directory inside the git common dir
+pub const TRASH_DIR_NAME: &str = "gwa-trash";
+
+/// Metadata file of a trash entry
+const ENTRY_FILE_NAME: &str = "entry.json";
+
+/// A removed worktree kept in the trash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    /// Name of the entry's directory in the trash
+    pub id: String,
+    pub branch: String,
+    /// Commit checked out when the worktree was removed
+    pub head: String,
+    /// Where the worktree was (and is restored to)
+    pub path: PathBuf,
+    /// Name of the worktree's directory under `<git common dir>/worktrees`
+    pub admin_name: String,
+    pub removed_at: DateTime<Utc>,
+}
+
+impl TrashEntry {
+    /// The ref keeping the recorded HEAD alive
+    fn keep_ref(&self) -> String {
+        format!("refs/gwa-trash/{}", self.id)
+    }
+
+    /// The ref keeping the staged files alive
+    fn index_ref(&self) -> String {
+        format!("refs/gwa-trash/{}-index", self.id)
+    }
+}
+
+/// The trash of a repository
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(repo: &Repository) -> Self {
+        Self {
+            dir: repo.common_dir().join(TRASH_DIR_NAME),
+        }
+    }
+
+    /// All entries, most recently removed first
+    pub fn list(&self) -> Vec<TrashEntry> {
+        let Ok(dirs) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<TrashEntry> = dirs
+            .flatten()
+            .filter_map(|dir| {
+                let id = dir.file_name().to_string_lossy().to_string();
+                let content = fs::read_to_string(dir.path().join(ENTRY_FILE_NAME)).ok()?;
+                match serde_json::from_str::<TrashEntry>(&content) {
+                    Ok(entry) => Some(TrashEntry { id, ..entry }),
+                    Err(e) => {
+                        warn!("Ignoring unreadable trash entry {}: {}", id, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.removed_at));
+        entries
+    }
+
+    /// The most recently removed worktree of a branch
+    pub fn find(&self, branch: &str) -> Option<TrashEntry> {
+        self.list().into_iter().find(|entry| entry.branch == branch)
+    }
+
+    /// Move the worktree of `branch` at `path` into the trash
+    pub fn put(&self, repo: &Repository, branch: &str, path: &Path) -> Result<TrashEntry> {
+        info!("Moving worktree at {} to the trash", path.display());
+
+        let head = git(path, &["rev-parse", "HEAD"])?;
+        let admin_dir = PathBuf::from(git(path, &["rev-parse", "--absolute-git-dir"])?);
+        let admin_name = admin_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| eyre!("Not a linked worktree: {}", path.display()))?;
+
+        let removed_at = Utc::now();
+        let stem = format!(
+            "{}-{}",
+            removed_at.format("%Y%m%dT%H%M%S"),
+            branch.replace('/', "-")
+        );
+        let mut id = stem.clone();
+        let mut n = 1;
+        while self.dir.join(&id).exists() {
+            n += 1;
+            id = format!("{}-{}", stem, n);
+        }
+
+        let entry = TrashEntry {
+            id,
+            branch: branch.to_string(),
+            head,
+            path: path.to_path_buf(),
+            admin_name,
+            removed_at,
+        };
+        let entry_dir = self.dir.join(&entry.id);
+        fs::create_dir_all(&entry_dir)
+            .with_context(|| format!("Failed to create {}", entry_dir.display()))?;
+        fs::write(
+            entry_dir.join(ENTRY_FILE_NAME),
+            serde_json::to_string_pretty(&entry)?,
+        )?;
+        git(repo.root(), &["update-ref", &entry.keep_ref(), &entry.head])?;
+        if let Err(e) = protect_index(repo, path, &entry) {
+            warn!("Staged files of {} are not protected: {}", branch, e);
+        }
+
+        if let Err(e) = fs::rename(path, entry_dir.join("worktree")) {
+            let _ = self.delete(repo, &entry);
+            return Err(eyre!(
+                "Failed to move {} to the trash (is it on another file system?): {}",
+                path.display(),
+                e
+            ));
+        }
+        if let Err(e) = fs::rename(&admin_dir, entry_dir.join("admin")) {
+            // Put the worktree back so git still knows it
+            let _ = fs::rename(entry_dir.join("worktree"), path);
+            let _ = self.delete(repo, &entry);
+            return Err(eyre!("Failed to move {}: {}", admin_dir.display(), e));
+        }
+
+        Ok(entry)
+    }
+
+    /// Move a worktree back from the trash to where it was
+    ///
+    /// Recreates the branch at the recorded HEAD if it was deleted. Returns
+    /// messages for the command log.
+    pub fn restore(&self, repo: &Repository, entry: &TrashEntry) -> Result<Vec<String>> {
+        info!(
+            "Restoring worktree of {} to {}",
+            entry.branch,
+            entry.path.display()
+        );
+
+        let admin_dir = repo.common_dir().join("worktrees").join(&entry.admin_name);
+        if entry.path.exists() {
+            return Err(eyre!("{} already exists", entry.path.display()));
+        }
+        if admin_dir.exists() {
+            return Err(eyre!(
+                "Another worktree is registered as {}",
+                admin_dir.display()
+            ));
+        }
+        if let Some(wt) = WorktreeAgent::new(repo)
+            .list()?
+            .into_iter()
+            .find(|w| w.branch.as_deref() == Some(entry.branch.as_str()))
+        {
+            return Err(eyre!(
+                "{} is checked out at {}",
+                entry.branch,
+                wt.path.display()
+            ));
+        }
+
+        let mut log_messages = Vec::new();
+        let branch_ref = format!("refs/heads/{}", entry.branch);
+        match repo.resolve_commit(&branch_ref) {
+            None => {
+                git(repo.root(), &["branch", &entry.branch, &entry.head])?;
+                log_messages.push(format!(
+                    "Recreated branch {} at {}",
+                    entry.branch,
+                    short_hash(&entry.head)
+                ));
+            }
+            Some(commit) if commit != entry.head => log_messages.push(format!(
+                "{} moved from {} to {} since the worktree was removed",
+                entry.branch,
+                short_hash(&entry.head),
+                short_hash(&commit)
+            )),
+            Some(_) => {}
+        }
+
+        let entry_dir = self.dir.join(&entry.id);
+        fs::create_dir_all(admin_dir.parent().unwrap_or(repo.common_dir()))?;
+        fs::rename(entry_dir.join("admin"), &admin_dir)
+            .with_context(|| format!("Failed to restore {}", admin_dir.display()))?;
+        if let Err(e) = fs::rename(entry_dir.join("worktree"), &entry.path) {
+            let _ = fs::rename(&admin_dir, entry_dir.join("admin"));
+            return Err(eyre!("Failed to restore {}: {}", entry.path.display(), e));
+        }
+
+        self.delete(repo, entry)?;
+        log_messages.push(format!("Restored worktree at {}", entry.path.display()));
+        Ok(log_messages)
+    }
+
+    /// Permanently delete the entries removed more than `retention_secs` ago
+    pub fn purge_expired(&self, repo: &Repository, retention_secs: u64) -> Vec<TrashEntry> {
+        let retention =
+            chrono::Duration::seconds(i64::try_from(retention_secs).unwrap_or(i64::MAX));
+        let now = Utc::now();
+
+        self.list()
+            .into_iter()
+            .filter(|entry| now.signed_duration_since(entry.removed_at) > retention)
+            .filter(|entry| match self.delete(repo, entry) {
+                Ok(()) => {
+                    info!("Purged {} from the trash", entry.id);
+                    true
+                }
+                Err(e) => {
+                    warn!("Failed to purge {} from the trash: {}", entry.id, e);
+                    false
+                }
+            })
+            .collect()
+    }
+
+    /// Delete an entry's directory and ref
+    fn delete(&self, repo: &Repository, entry: &TrashEntry) -> Result<()> {
+        let entry_dir = self.dir.join(&entry.id);
+        if entry_dir.exists() {
+            fs::remove_dir_all(&entry_dir)
+                .with_context(|| format!("Failed to delete {}", entry_dir.display()))?;
+        }
+        // The refs may not have been created yet
+        let _ = git(repo.root(), &["update-ref", "-d", &entry.keep_ref()]);
+        let _ = git(repo.root(), &["update-ref", "-d", &entry.index_ref()]);
+        Ok(())
+    }
+}
+
+/// Point the entry's index ref at a commit of the worktree's index
+///
+/// Fails if the index has conflicts, as git can't write a tree of it.
+fn protect_index(repo: &Repository, path: &Path, entry: &TrashEntry) -> Result<()> {
+    let tree = git(path, &["write-tree"])?;
+    let commit = git(
+        path,
+        &[
+            "-c",
+            "user.name=gwa",
+            "-c",
+            "user.email=gwa@localhost",
+            "commit-tree",
+            &tree,
+            "-p",
+            &entry.head,
+            "-m",
+            &format!("Index of {} in the gwa trash", entry.branch),
+        ],
+    )?;
+    git(repo.root(), &["update-ref", &entry.index_ref(), &commit])?;
+    Ok(())
+}
+
+/// Run git in `dir` and return its trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+
+    if !output.status.success() {
+        return Err(eyre!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watcher::tests::{self as watcher_tests, commit, git};
+
+    /// The watcher tests' repository with `file.txt` committed on `main` and a worktree
+    /// of `feature` at `<dir>/feature`, with a staged, a modified and an untracked file
+    fn setup(name: &str) -> (PathBuf, Repository, PathBuf) {
+        let (dir, repo) = watcher_tests::setup(&format!("trash-{}", name), &[]);
+        let main = dir.join("main");
+        fs::write(main.join("file.txt"), "committed\n").unwrap();
+        git(&main, &["add", "file.txt"]);
+        commit(&main, "add file");
+
+        let worktree = dir.join("feature");
+        git(
+            &main,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                worktree.to_str().unwrap(),
+            ],
+        );
+        fs::write(worktree.join("staged.txt"), "staged\n").unwrap();
+        git(&worktree, &["add", "staged.txt"]);
+        fs::write(worktree.join("file.txt"), "modified\n").unwrap();
+        fs::write(worktree.join("untracked.txt"), "untracked\n").unwrap();
+
+        (dir, repo, worktree)
+    }
+
+    fn assert_restored(repo: &Repository, worktree: &Path, head: &str) {
+        assert_eq!(git(worktree, &["rev-parse", "HEAD"]), head);
+        assert_eq!(git(worktree, &["branch", "--show-current"]), "feature");
+        assert_eq!(git(worktree, &["show", ":staged.txt"]), "staged");
+        assert_eq!(
+            fs::read_to_string(worktree.join("file.txt")).unwrap(),
+            "modified\n"
+        );
+        assert!(worktree.join("untracked.txt").exists());
+        assert_eq!(git(repo.root(), &["for-each-ref", "refs/gwa-trash"]), "");
+    }
+
+    #[test]
+    fn test_put_and_restore() {
+        let (dir, repo, worktree) = setup("restore");
+        let trash = Trash::new(&repo);
+
+        let entry = trash.put(&repo, "feature", &worktree).unwrap();
+        assert!(!worktree.exists());
+        assert_eq!(trash.find("feature"), Some(entry.clone()));
+
+        // Only the refs keep the staged file from being pruned
+        git(repo.root(), &["gc", "-q", "--prune=now"]);
+
+        trash.restore(&repo, &entry).unwrap();
+        assert_restored(&repo, &worktree, &entry.head);
+        assert!(trash.list().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_deleted_branch() {
+        let (dir, repo, worktree) = setup("deleted");
+        let trash = Trash::new(&repo);
+
+        let entry = trash.put(&repo, "feature", &worktree).unwrap();
+        git(repo.root(), &["branch", "-q", "-D", "feature"]);
+
+        let log_messages = trash.restore(&repo, &entry).unwrap();
+        assert!(log_messages[0].starts_with("Recreated branch feature"));
+        assert_restored(&repo, &worktree, &entry.head);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let (dir, repo, worktree) = setup("purge");
+        let trash = Trash::new(&repo);
+
+        let entry = trash.put(&repo, "feature", &worktree).unwrap();
+        assert!(trash.purge_expired(&repo, 3600).is_empty());
+
+        let purged = trash.purge_expired(&repo, 0);
+        assert_eq!(purged, vec![entry]);
+        assert!(trash.list().is_empty());
+        assert_eq!(git(repo.root(), &["for-each-ref", "refs/gwa-trash"]), "");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
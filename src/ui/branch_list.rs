@@ -7,11 +7,18 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState, StatefulWidget},
 };
+use serde::Serialize;
+
+use std::collections::HashSet;
 
 use super::Theme;
+use super::fuzzy::fuzzy_match;
+use crate::config::SortMode;
+use crate::git::WorktreeStatus;
 
 /// Status of a branch
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BranchStatus {
     /// Remote branch with no local worktree
     Remote,
@@ -27,18 +34,149 @@ pub enum BranchStatus {
     RunningHook,
 }
 
+/// Why a worktree was not fast-forwarded to its moved remote branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    /// The worktree has uncommitted changes
+    Dirty,
+    /// The worktree has local commits the remote branch lacks
+    Diverged,
+}
+
+impl StaleReason {
+    /// Short label for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            StaleReason::Dirty => "dirty",
+            StaleReason::Diverged => "diverged",
+        }
+    }
+}
+
 /// A branch item for display
 #[derive(Debug, Clone)]
 pub struct BranchItem {
     pub name: String,
+    /// Remote the branch comes from, shown when several remotes are watched
+    pub remote: Option<String>,
     pub status: BranchStatus,
     pub is_default: bool,
+    /// Changes, ahead/behind and stashes of the worktree (once computed)
+    pub worktree_status: Option<WorktreeStatus>,
+    /// Set when the worktree is behind its remote branch but could not be fast-forwarded
+    pub stale: Option<StaleReason>,
+    /// Set when the worktree's remote branch was deleted (cleanup policy)
+    pub orphaned: bool,
+    /// Committer date of the branch's latest commit (Unix timestamp)
+    pub commit_time: i64,
+    /// Author of the branch's latest commit
+    pub author: String,
+}
+
+impl BranchItem {
+    /// Whether the branch has a worktree on disk (or a missing one to prune)
+    fn has_worktree(&self) -> bool {
+        matches!(
+            self.status,
+            BranchStatus::LocalActive | BranchStatus::LocalPrunable
+        )
+    }
+}
+
+/// The group of a branch in the tree view: its first path segment (e.g. "feature/")
+fn group_prefix(name: &str) -> Option<&str> {
+    name.find('/').map(|i| &name[..=i])
+}
+
+/// Which branches the list shows, besides the text filter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BranchFilter {
+    #[default]
+    All,
+    /// Only branches with a local worktree
+    Local,
+    /// Only branches without a local worktree
+    RemoteOnly,
+    /// Only branches with a running hook
+    RunningHooks,
+}
+
+impl BranchFilter {
+    /// Check if a branch passes the filter
+    pub fn matches(&self, item: &BranchItem) -> bool {
+        match self {
+            BranchFilter::All => true,
+            BranchFilter::Local => item.has_worktree(),
+            BranchFilter::RemoteOnly => item.status == BranchStatus::Remote,
+            BranchFilter::RunningHooks => item.status == BranchStatus::RunningHook,
+        }
+    }
+
+    /// Short label for the list title
+    pub fn label(&self) -> &'static str {
+        match self {
+            BranchFilter::All => "all",
+            BranchFilter::Local => "local",
+            BranchFilter::RemoteOnly => "remote only",
+            BranchFilter::RunningHooks => "running hooks",
+        }
+    }
+}
+
+/// A row of the list
+#[derive(Debug, Clone)]
+enum Row {
+    /// Header of a prefix group in the tree view
+    Group {
+        prefix: String,
+        /// Branches in the group that pass the filters
+        count: usize,
+        collapsed: bool,
+    },
+    /// A branch that passes the filters
+    Branch {
+        /// Index into `BranchListState::items`
+        index: usize,
+        /// Char indices of the name matched by the fuzzy filter
+        matches: Vec<usize>,
+        /// Shown indented under its group header, without the group prefix
+        grouped: bool,
+    },
+}
+
+/// What a row shows, to keep it selected while the rows change
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RowKey {
+    Group(String),
+    Branch(String),
 }
 
 /// Branch list widget state
 pub struct BranchListState {
     pub list_state: ListState,
+    /// All branches, filtered or not
     pub items: Vec<BranchItem>,
+    /// Fuzzy filter text
+    pub filter: String,
+    /// Whether the filter is being typed
+    pub filter_editing: bool,
+    /// Status filter
+    pub status_filter: BranchFilter,
+    /// Order of the branches (fuzzy matches come first while filtering)
+    pub sort_mode: SortMode,
+    /// Show the branches as a tree grouped by path prefix
+    pub grouped: bool,
+    /// Prefixes of collapsed groups
+    collapsed: HashSet<String>,
+    /// Rows shown, in display order (`list_state` indexes into this)
+    rows: Vec<Row>,
+    /// Number of branches that pass the filters
+    match_count: usize,
+    /// Names of the branches marked for a bulk action
+    marked: HashSet<String>,
+    /// Row where a visual range selection started
+    visual_anchor: Option<RowKey>,
 }
 
 impl BranchListState {
@@ -46,16 +184,26 @@ impl BranchListState {
         Self {
             list_state: ListState::default(),
             items: Vec::new(),
+            filter: String::new(),
+            filter_editing: false,
+            status_filter: BranchFilter::default(),
+            sort_mode: SortMode::default(),
+            grouped: false,
+            collapsed: HashSet::new(),
+            rows: Vec::new(),
+            match_count: 0,
+            marked: HashSet::new(),
+            visual_anchor: None,
         }
     }
 
     pub fn select_next(&mut self) {
-        if self.items.is_empty() {
+        if self.rows.is_empty() {
             return;
         }
         let i = match self.list_state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= self.rows.len() - 1 {
                     0
                 } else {
                     i + 1
@@ -67,13 +215,13 @@ impl BranchListState {
     }
 
     pub fn select_previous(&mut self) {
-        if self.items.is_empty() {
+        if self.rows.is_empty() {
             return;
         }
         let i = match self.list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    self.rows.len() - 1
                 } else {
                     i - 1
                 }
@@ -83,8 +231,12 @@ impl BranchListState {
         self.list_state.select(Some(i));
     }
 
+    /// The selected branch (None if a group header is selected)
     pub fn selected(&self) -> Option<&BranchItem> {
-        self.list_state.selected().and_then(|i| self.items.get(i))
+        match self.list_state.selected().and_then(|i| self.rows.get(i))? {
+            Row::Branch { index, .. } => Some(&self.items[*index]),
+            Row::Group { .. } => None,
+        }
     }
 
     pub fn selected_branch(&self) -> Option<String> {
@@ -92,28 +244,332 @@ impl BranchListState {
     }
 
     pub fn select_by_name(&mut self, name: &str) {
-        if let Some(idx) = self.items.iter().position(|item| item.name == name) {
-            self.list_state.select(Some(idx));
-        }
+        self.select_key(&RowKey::Branch(name.to_string()));
+    }
+
+    /// Number of branches that pass the filters
+    pub fn visible_count(&self) -> usize {
+        self.match_count
+    }
+
+    /// Whether a text or status filter hides some branches
+    pub fn is_filtered(&self) -> bool {
+        !self.filter.is_empty() || self.status_filter != BranchFilter::All
     }
 
     pub fn update_items(&mut self, items: Vec<BranchItem>) {
-        // Remember the selected branch name before updating
-        let selected_branch_name = self.selected_branch();
+        // Rows index into the old items
+        let selected_key = self.selected_key();
         self.items = items;
+        // Branches that went away can't be acted on
+        let items = &self.items;
+        self.marked
+            .retain(|name| items.iter().any(|item| &item.name == name));
+        self.sort_items();
+        self.rebuild_rows(selected_key);
+    }
 
-        // Try to re-select the same branch by name
-        if let Some(name) = selected_branch_name
-            && let Some(idx) = self.items.iter().position(|item| item.name == name)
-        {
+    /// Change the order of the branches
+    pub fn set_sort_mode(&mut self, sort_mode: SortMode) {
+        let selected_key = self.selected_key();
+        self.sort_mode = sort_mode;
+        self.sort_items();
+        self.rebuild_rows(selected_key);
+    }
+
+    /// Switch between the flat list and the tree grouped by prefix
+    pub fn set_grouped(&mut self, grouped: bool) {
+        self.grouped = grouped;
+        self.refilter();
+    }
+
+    /// Set the fuzzy filter text
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.refilter();
+    }
+
+    /// Show only branches matching `filter`, or all of them if it is already set
+    pub fn toggle_status_filter(&mut self, filter: BranchFilter) {
+        self.status_filter = if self.status_filter == filter {
+            BranchFilter::All
+        } else {
+            filter
+        };
+        self.refilter();
+    }
+
+    /// Collapse or expand the group of the selected row
+    ///
+    /// Returns false if the selection isn't in a group. Collapsing from a branch
+    /// selects its group header.
+    pub fn set_group_collapsed(&mut self, collapsed: bool) -> bool {
+        let prefix = match self.list_state.selected().and_then(|i| self.rows.get(i)) {
+            Some(Row::Group { prefix, .. }) => prefix.clone(),
+            Some(Row::Branch {
+                index,
+                grouped: true,
+                ..
+            }) => match group_prefix(&self.items[*index].name) {
+                Some(prefix) => prefix.to_string(),
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        if collapsed {
+            self.collapsed.insert(prefix.clone());
+        } else {
+            self.collapsed.remove(&prefix);
+        }
+        self.refilter();
+        if collapsed {
+            self.select_key(&RowKey::Group(prefix));
+        }
+        true
+    }
+
+    /// Collapse or expand the selected group header; false if no header is selected
+    pub fn toggle_selected_group(&mut self) -> bool {
+        match self.list_state.selected().and_then(|i| self.rows.get(i)) {
+            Some(Row::Group { collapsed, .. }) => {
+                let collapse = !collapsed;
+                self.set_group_collapsed(collapse)
+            }
+            _ => false,
+        }
+    }
+
+    /// Mark or unmark the selected branch; on a group header, all its branches
+    pub fn toggle_mark(&mut self) {
+        let names = match self.list_state.selected().and_then(|i| self.rows.get(i)) {
+            Some(Row::Branch { index, .. }) => vec![self.items[*index].name.clone()],
+            Some(Row::Group { prefix, .. }) => self
+                .items
+                .iter()
+                .filter(|item| {
+                    group_prefix(&item.name) == Some(prefix.as_str())
+                        && self.status_filter.matches(item)
+                        && fuzzy_match(&self.filter, &item.name).is_some()
+                })
+                .map(|item| item.name.clone())
+                .collect(),
+            None => return,
+        };
+
+        if names.iter().all(|name| self.marked.contains(name)) {
+            for name in &names {
+                self.marked.remove(name);
+            }
+        } else {
+            self.marked.extend(names);
+        }
+    }
+
+    /// Start a visual range at the selected row, or mark the range and end it
+    pub fn toggle_visual(&mut self) {
+        if self.visual_anchor.is_some() {
+            let range = self.visual_branches();
+            self.marked.extend(range);
+            self.visual_anchor = None;
+        } else {
+            self.visual_anchor = self.selected_key();
+        }
+    }
+
+    /// Whether a visual range selection is in progress
+    pub fn is_visual(&self) -> bool {
+        self.visual_anchor.is_some()
+    }
+
+    /// Whether any branches are marked (or in the visual range)
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty() || self.visual_anchor.is_some()
+    }
+
+    /// Drop all marks and the visual range
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.visual_anchor = None;
+    }
+
+    /// Branches a bulk action applies to: marked ones and the visual range, in
+    /// list order
+    pub fn marked_items(&self) -> Vec<&BranchItem> {
+        let range = self.visual_branches();
+        self.items
+            .iter()
+            .filter(|item| self.marked.contains(&item.name) || range.contains(&item.name))
+            .collect()
+    }
+
+    fn is_marked(&self, name: &str, range: &HashSet<String>) -> bool {
+        self.marked.contains(name) || range.contains(name)
+    }
+
+    /// Names of the branches between the visual anchor and the selected row
+    fn visual_branches(&self) -> HashSet<String> {
+        let Some(anchor) = &self.visual_anchor else {
+            return HashSet::new();
+        };
+        let (Some(start), Some(end)) = (self.position_of(anchor), self.list_state.selected())
+        else {
+            return HashSet::new();
+        };
+        let (start, end) = (start.min(end), start.max(end));
+        self.rows[start..=end.min(self.rows.len().saturating_sub(1))]
+            .iter()
+            .filter_map(|row| match row {
+                Row::Branch { index, .. } => Some(self.items[*index].name.clone()),
+                Row::Group { .. } => None,
+            })
+            .collect()
+    }
+
+    fn sort_items(&mut self) {
+        match self.sort_mode {
+            SortMode::Status => self.items.sort_by(|a, b| {
+                // Active worktrees first, then by name
+                b.has_worktree()
+                    .cmp(&a.has_worktree())
+                    .then_with(|| a.name.cmp(&b.name))
+            }),
+            SortMode::Name => self.items.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortMode::CommitDate => self.items.sort_by(|a, b| {
+                b.commit_time
+                    .cmp(&a.commit_time)
+                    .then_with(|| a.name.cmp(&b.name))
+            }),
+            SortMode::Author => self.items.sort_by(|a, b| {
+                a.author
+                    .to_lowercase()
+                    .cmp(&b.author.to_lowercase())
+                    .then_with(|| a.name.cmp(&b.name))
+            }),
+        }
+    }
+
+    fn selected_key(&self) -> Option<RowKey> {
+        match self.list_state.selected().and_then(|i| self.rows.get(i))? {
+            Row::Group { prefix, .. } => Some(RowKey::Group(prefix.clone())),
+            Row::Branch { index, .. } => Some(RowKey::Branch(self.items[*index].name.clone())),
+        }
+    }
+
+    fn position_of(&self, key: &RowKey) -> Option<usize> {
+        self.rows.iter().position(|row| match (row, key) {
+            (Row::Group { prefix, .. }, RowKey::Group(p)) => prefix == p,
+            (Row::Branch { index, .. }, RowKey::Branch(name)) => &self.items[*index].name == name,
+            _ => false,
+        })
+    }
+
+    fn select_key(&mut self, key: &RowKey) -> bool {
+        let position = self.position_of(key);
+        if let Some(idx) = position {
             self.list_state.select(Some(idx));
+        }
+        position.is_some()
+    }
+
+    /// Recompute the visible rows, keeping the selected row selected if it is
+    /// still shown
+    pub fn refilter(&mut self) {
+        // Remember the selected row before updating
+        let selected_key = self.selected_key();
+        self.rebuild_rows(selected_key);
+    }
+
+    /// Recompute the visible rows and select `selected_key` again (or the first row)
+    fn rebuild_rows(&mut self, selected_key: Option<RowKey>) {
+        let mut scored: Vec<(i64, usize, Vec<usize>)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| self.status_filter.matches(item))
+            .filter_map(|(index, item)| {
+                let (score, matches) = fuzzy_match(&self.filter, &item.name)?;
+                Some((score, index, matches))
+            })
+            .collect();
+        // Best matches first; without a filter all scores are equal (stable sort)
+        scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        self.match_count = scored.len();
+
+        self.rows = if self.grouped {
+            self.group_rows(scored)
+        } else {
+            scored
+                .into_iter()
+                .map(|(_, index, matches)| Row::Branch {
+                    index,
+                    matches,
+                    grouped: false,
+                })
+                .collect()
+        };
+
+        // Try to re-select the same row
+        if let Some(key) = selected_key
+            && self.select_key(&key)
+        {
             return;
         }
 
-        // Fallback: select first item if nothing selected
-        if self.list_state.selected().is_none() && !self.items.is_empty() {
-            self.list_state.select(Some(0));
+        // Fallback: select first row
+        self.list_state.select((!self.rows.is_empty()).then_some(0));
+    }
+
+    /// Build the tree view rows: groups appear where their first branch would be
+    fn group_rows(&self, scored: Vec<(i64, usize, Vec<usize>)>) -> Vec<Row> {
+        enum Entry {
+            Single(usize, Vec<usize>),
+            Group(String, Vec<(usize, Vec<usize>)>),
+        }
+
+        let mut entries: Vec<Entry> = Vec::new();
+        for (_, index, matches) in scored {
+            let Some(prefix) = group_prefix(&self.items[index].name) else {
+                entries.push(Entry::Single(index, matches));
+                continue;
+            };
+            let existing = entries.iter_mut().find_map(|entry| match entry {
+                Entry::Group(p, members) if p == prefix => Some(members),
+                _ => None,
+            });
+            match existing {
+                Some(members) => members.push((index, matches)),
+                None => entries.push(Entry::Group(prefix.to_string(), vec![(index, matches)])),
+            }
+        }
+
+        let mut rows = Vec::new();
+        for entry in entries {
+            match entry {
+                Entry::Single(index, matches) => rows.push(Row::Branch {
+                    index,
+                    matches,
+                    grouped: false,
+                }),
+                Entry::Group(prefix, members) => {
+                    // Matches of a filter are never hidden in a collapsed group
+                    let collapsed = self.filter.is_empty() && self.collapsed.contains(&prefix);
+                    rows.push(Row::Group {
+                        prefix,
+                        count: members.len(),
+                        collapsed,
+                    });
+                    if !collapsed {
+                        rows.extend(members.into_iter().map(|(index, matches)| Row::Branch {
+                            index,
+                            matches,
+                            grouped: true,
+                        }));
+                    }
+                }
+            }
         }
+        rows
     }
 }
 
@@ -146,15 +602,111 @@ impl<'a> BranchListWidget<'a> {
     }
 }
 
+impl BranchListWidget<'_> {
+    /// Header row of a prefix group in the tree view
+    fn group_line(&self, prefix: &str, count: usize, collapsed: bool) -> Line<'static> {
+        let arrow = if collapsed { "▸" } else { "▾" };
+        Line::from(vec![
+            Span::styled(
+                format!("{} {}", arrow, prefix),
+                Style::default()
+                    .fg(self.theme.secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" ({})", count),
+                Style::default().fg(self.theme.muted),
+            ),
+        ])
+    }
+
+    /// Split `text` into spans, underlining the chars matched by the filter
+    ///
+    /// `matches` are char indices into the full name, `offset` is the index of the
+    /// first char of `text` in it.
+    fn highlight(
+        &self,
+        text: &str,
+        offset: usize,
+        matches: &[usize],
+        style: Style,
+    ) -> Vec<Span<'static>> {
+        let match_style = style
+            .fg(self.theme.warning)
+            .add_modifier(Modifier::UNDERLINED);
+
+        let mut spans = Vec::new();
+        let mut run = String::new();
+        let mut run_matched = false;
+        for (i, c) in text.chars().enumerate() {
+            let matched = matches.contains(&(offset + i));
+            if matched != run_matched && !run.is_empty() {
+                let run_style = if run_matched { match_style } else { style };
+                spans.push(Span::styled(std::mem::take(&mut run), run_style));
+            }
+            run_matched = matched;
+            run.push(c);
+        }
+        if !run.is_empty() {
+            let run_style = if run_matched { match_style } else { style };
+            spans.push(Span::styled(run, run_style));
+        }
+        spans
+    }
+
+    /// Compact worktree status, e.g. " ~2 ?1 ↑3 ↓1 ⚑1" (only the non-zero parts)
+    fn worktree_status_spans(&self, status: &WorktreeStatus) -> Vec<Span<'static>> {
+        let parts = [
+            (status.changed, "~", self.theme.warning),
+            (status.untracked, "?", self.theme.warning),
+            (status.ahead, "↑", self.theme.primary),
+            (status.behind, "↓", self.theme.secondary),
+            (status.stashes, "⚑", self.theme.muted),
+        ];
+
+        parts
+            .into_iter()
+            .filter(|(count, _, _)| *count > 0)
+            .map(|(count, symbol, color)| {
+                Span::styled(format!(" {}{}", symbol, count), Style::default().fg(color))
+            })
+            .collect()
+    }
+}
+
 impl StatefulWidget for BranchListWidget<'_> {
     type State = BranchListState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let visual_range = state.visual_branches();
+        let show_marks = state.has_marks();
         let items: Vec<ListItem> = state
-            .items
+            .rows
             .iter()
-            .map(|item| {
-                let (indicator, indicator_style) = self.status_indicator(&item.status);
+            .map(|row| {
+                let (item, matches, grouped) = match row {
+                    Row::Group {
+                        prefix,
+                        count,
+                        collapsed,
+                    } => return ListItem::new(self.group_line(prefix, *count, *collapsed)),
+                    Row::Branch {
+                        index,
+                        matches,
+                        grouped,
+                    } => (&state.items[*index], matches, *grouped),
+                };
+
+                let (indicator, mut indicator_style) = self.status_indicator(&item.status);
+                // Worktrees with work that exists nowhere else stand out
+                if item.status == BranchStatus::LocalActive
+                    && item
+                        .worktree_status
+                        .as_ref()
+                        .is_some_and(WorktreeStatus::has_unsaved_work)
+                {
+                    indicator_style = Style::default().fg(self.theme.warning);
+                }
 
                 let name_style = if item.is_default {
                     Style::default()
@@ -187,24 +739,123 @@ impl StatefulWidget for BranchListWidget<'_> {
                     _ => Span::raw(""),
                 };
 
-                ListItem::new(Line::from(vec![
-                    Span::styled(format!("{} ", indicator), indicator_style),
-                    Span::styled(&item.name, name_style),
-                    suffix,
-                    status_label,
-                ]))
+                let worktree_label = item
+                    .worktree_status
+                    .as_ref()
+                    .map(|status| self.worktree_status_spans(status))
+                    .unwrap_or_default();
+
+                let stale_label = match item.stale {
+                    Some(reason) => Span::styled(
+                        format!(" ⚠ behind, {}", reason.label()),
+                        Style::default().fg(self.theme.warning),
+                    ),
+                    None => Span::raw(""),
+                };
+
+                let orphaned_label = if item.orphaned {
+                    Span::styled(" ✗ remote deleted", Style::default().fg(self.theme.muted))
+                } else {
+                    Span::raw("")
+                };
+
+                // Grouped rows drop the prefix their header shows
+                let hidden_len = if grouped {
+                    group_prefix(&item.name).map_or(0, str::len)
+                } else {
+                    0
+                };
+                let (hidden, shown) = item.name.split_at(hidden_len);
+                let offset = hidden.chars().count();
+
+                // Branches of other remotes already carry the remote in their name
+                let prefix_len = item
+                    .remote
+                    .as_ref()
+                    .filter(|remote| shown.starts_with(&format!("{}/", remote)))
+                    .map_or(0, |remote| remote.len() + 1);
+                let (prefix, name) = shown.split_at(prefix_len);
+
+                let indent = if grouped { "  " } else { "" };
+                let mut spans = Vec::new();
+                // Marks get a column of their own while any are set
+                if show_marks {
+                    spans.push(if state.is_marked(&item.name, &visual_range) {
+                        Span::styled("✚ ", Style::default().fg(self.theme.warning))
+                    } else {
+                        Span::raw("  ")
+                    });
+                }
+                spans.push(Span::styled(
+                    format!("{}{} ", indent, indicator),
+                    indicator_style,
+                ));
+                spans.extend(self.highlight(
+                    prefix,
+                    offset,
+                    matches,
+                    Style::default().fg(self.theme.muted),
+                ));
+                spans.extend(self.highlight(
+                    name,
+                    offset + prefix.chars().count(),
+                    matches,
+                    name_style,
+                ));
+                spans.push(suffix);
+                spans.push(status_label);
+                spans.extend(worktree_label);
+                spans.push(stale_label);
+                spans.push(orphaned_label);
+
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
+        let mut title = vec![Span::styled(
+            self.title,
+            Style::default()
+                .fg(self.theme.primary)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if state.is_filtered() || state.filter_editing {
+            title.push(Span::styled(
+                format!("{}/{} ", state.visible_count(), state.items.len()),
+                Style::default().fg(self.theme.muted),
+            ));
+        }
+        let marked_count = state.marked_items().len();
+        if marked_count > 0 || state.is_visual() {
+            let visual = if state.is_visual() { " (visual)" } else { "" };
+            title.push(Span::styled(
+                format!("{} marked{} ", marked_count, visual),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        if state.sort_mode != SortMode::Status {
+            title.push(Span::styled(
+                format!("↕{} ", state.sort_mode.name()),
+                Style::default().fg(self.theme.muted),
+            ));
+        }
+        if state.status_filter != BranchFilter::All {
+            title.push(Span::styled(
+                format!("[{}] ", state.status_filter.label()),
+                Style::default().fg(self.theme.secondary),
+            ));
+        }
+        if !state.filter.is_empty() || state.filter_editing {
+            let cursor = if state.filter_editing { "▏" } else { "" };
+            title.push(Span::styled(
+                format!("/{}{} ", state.filter, cursor),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(self.theme.primary))
-            .title(Span::styled(
-                self.title,
-                Style::default()
-                    .fg(self.theme.primary)
-                    .add_modifier(Modifier::BOLD),
-            ));
+            .title(Line::from(title));
 
         let list = List::new(items)
             .block(block)
@@ -219,3 +870,186 @@ impl StatefulWidget for BranchListWidget<'_> {
         StatefulWidget::render(list, area, buf, &mut state.list_state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, status: BranchStatus, commit_time: i64, author: &str) -> BranchItem {
+        BranchItem {
+            name: name.to_string(),
+            remote: None,
+            status,
+            is_default: false,
+            worktree_status: None,
+            stale: None,
+            orphaned: false,
+            commit_time,
+            author: author.to_string(),
+        }
+    }
+
+    fn list(items: Vec<BranchItem>) -> BranchListState {
+        let mut state = BranchListState::new();
+        state.update_items(items);
+        state
+    }
+
+    /// The rows as shown: branch names, and `+`/`-` and the prefix for collapsed
+    /// and expanded groups
+    fn rows(state: &BranchListState) -> Vec<String> {
+        state
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Group {
+                    prefix, collapsed, ..
+                } => format!("{}{}", if *collapsed { "+" } else { "-" }, prefix),
+                Row::Branch { index, .. } => state.items[*index].name.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let mut state = list(vec![
+            item("fix/b", BranchStatus::Remote, 300, "bob"),
+            item("feature/a", BranchStatus::LocalActive, 100, "Carol"),
+            item("main", BranchStatus::Remote, 200, "alice"),
+        ]);
+        assert_eq!(rows(&state), ["feature/a", "fix/b", "main"]);
+
+        state.set_sort_mode(SortMode::Name);
+        assert_eq!(rows(&state), ["feature/a", "fix/b", "main"]);
+        state.set_sort_mode(SortMode::CommitDate);
+        assert_eq!(rows(&state), ["fix/b", "main", "feature/a"]);
+        state.set_sort_mode(SortMode::Author);
+        assert_eq!(rows(&state), ["main", "fix/b", "feature/a"]);
+
+        // The selection stays on its branch
+        state.select_by_name("fix/b");
+        state.set_sort_mode(SortMode::CommitDate);
+        assert_eq!(state.selected_branch().as_deref(), Some("fix/b"));
+    }
+
+    #[test]
+    fn test_group_by_prefix() {
+        let mut state = list(vec![
+            item("feature/a", BranchStatus::Remote, 0, ""),
+            item("feature/b", BranchStatus::Remote, 0, ""),
+            item("fix/c", BranchStatus::Remote, 0, ""),
+            item("main", BranchStatus::Remote, 0, ""),
+        ]);
+        state.set_sort_mode(SortMode::Name);
+        state.set_grouped(true);
+        assert_eq!(
+            rows(&state),
+            [
+                "-feature/",
+                "feature/a",
+                "feature/b",
+                "-fix/",
+                "fix/c",
+                "main"
+            ]
+        );
+
+        // Collapsing from a branch selects its group header
+        state.select_by_name("feature/b");
+        assert!(state.set_group_collapsed(true));
+        assert_eq!(rows(&state), ["+feature/", "-fix/", "fix/c", "main"]);
+        assert_eq!(state.selected_branch(), None);
+        assert!(state.toggle_selected_group());
+        assert_eq!(rows(&state).len(), 6);
+        state.select_by_name("main");
+        assert!(!state.set_group_collapsed(true));
+
+        // A filter shows its matches in collapsed groups
+        state.select_by_name("feature/a");
+        state.set_group_collapsed(true);
+        state.set_filter("fb".to_string());
+        assert_eq!(rows(&state), ["-feature/", "feature/b"]);
+        assert_eq!(state.visible_count(), 1);
+    }
+
+    fn marked(state: &BranchListState) -> Vec<&str> {
+        state
+            .marked_items()
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_marks() {
+        let mut state = list(vec![
+            item("feature/a", BranchStatus::Remote, 0, ""),
+            item("feature/b", BranchStatus::LocalActive, 0, ""),
+            item("fix/c", BranchStatus::Remote, 0, ""),
+        ]);
+        state.set_sort_mode(SortMode::Name);
+
+        state.select_by_name("fix/c");
+        state.toggle_mark();
+        state.select_by_name("feature/a");
+        state.toggle_mark();
+        assert_eq!(marked(&state), ["feature/a", "fix/c"]);
+        state.toggle_mark();
+        assert_eq!(marked(&state), ["fix/c"]);
+
+        // Marks of branches that went away are dropped
+        state.update_items(vec![
+            item("feature/a", BranchStatus::Remote, 0, ""),
+            item("feature/b", BranchStatus::LocalActive, 0, ""),
+        ]);
+        assert!(!state.has_marks());
+
+        // A group header marks the group's branches that pass the filters, or
+        // unmarks them if they all are
+        state.set_grouped(true);
+        state.toggle_status_filter(BranchFilter::Local);
+        assert_eq!(rows(&state), ["-feature/", "feature/b"]);
+        state.list_state.select(Some(0));
+        state.toggle_mark();
+        state.toggle_status_filter(BranchFilter::Local);
+        assert_eq!(marked(&state), ["feature/b"]);
+        state.list_state.select(Some(0));
+        state.toggle_mark();
+        assert_eq!(marked(&state), ["feature/a", "feature/b"]);
+        state.toggle_mark();
+        assert!(!state.has_marks());
+    }
+
+    #[test]
+    fn test_visual_range() {
+        let mut state = list(vec![
+            item("a", BranchStatus::Remote, 0, ""),
+            item("b", BranchStatus::Remote, 0, ""),
+            item("c", BranchStatus::Remote, 0, ""),
+            item("d", BranchStatus::Remote, 0, ""),
+        ]);
+        state.set_sort_mode(SortMode::Name);
+
+        // The range follows the selection, either way from the anchor
+        state.select_by_name("c");
+        state.toggle_visual();
+        assert!(state.is_visual());
+        state.select_next();
+        assert_eq!(marked(&state), ["c", "d"]);
+        state.select_by_name("a");
+        assert_eq!(marked(&state), ["a", "b", "c"]);
+
+        // Ending it keeps the range marked
+        state.toggle_visual();
+        assert!(!state.is_visual());
+        state.select_by_name("d");
+        assert_eq!(marked(&state), ["a", "b", "c"]);
+
+        state.toggle_visual();
+        assert!(state.has_marks());
+        state.clear_marks();
+        assert!(!state.has_marks());
+        assert!(!state.is_visual());
+        assert!(marked(&state).is_empty());
+    }
+}
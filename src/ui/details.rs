@@ -0,0 +1,181 @@
+//! Branch details widget
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget,
+        Widget,
+    },
+};
+
+use crate::git::BranchDetails;
+
+use super::{LogsState, Theme};
+
+/// Recent commits, merge-base and diff stat of the selected branch
+pub struct BranchDetailsWidget<'a> {
+    branch: Option<&'a str>,
+    /// None while loading, the error message if git failed
+    details: Option<&'a Result<BranchDetails, String>>,
+    theme: &'a Theme,
+    state: &'a mut LogsState,
+}
+
+impl<'a> BranchDetailsWidget<'a> {
+    pub fn new(
+        branch: Option<&'a str>,
+        details: Option<&'a Result<BranchDetails, String>>,
+        theme: &'a Theme,
+        state: &'a mut LogsState,
+    ) -> Self {
+        Self {
+            branch,
+            details,
+            theme,
+            state,
+        }
+    }
+
+    fn heading(&self, text: String) -> Line<'static> {
+        Line::from(Span::styled(
+            text,
+            Style::default()
+                .fg(self.theme.secondary)
+                .add_modifier(Modifier::BOLD),
+        ))
+    }
+
+    fn render_details(&self, details: &BranchDetails) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+
+        match (&details.base, &details.merge_base) {
+            (Some(base), Some(merge_base)) => lines.push(Line::from(vec![
+                Span::styled("Base: ", Style::default().fg(self.theme.muted)),
+                Span::styled(base.clone(), Style::default().fg(self.theme.fg)),
+                Span::styled("  merge-base ", Style::default().fg(self.theme.muted)),
+                Span::styled(merge_base.clone(), Style::default().fg(self.theme.primary)),
+            ])),
+            (Some(base), None) => lines.push(Line::from(Span::styled(
+                format!("No common history with {}", base),
+                Style::default().fg(self.theme.warning),
+            ))),
+            _ => lines.push(Line::from(Span::styled(
+                "No base branch configured",
+                Style::default().fg(self.theme.muted),
+            ))),
+        }
+        lines.push(Line::raw(""));
+
+        lines.push(self.heading("Recent commits".to_string()));
+        for commit in &details.commits {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} ", commit.hash),
+                    Style::default().fg(self.theme.primary),
+                ),
+                Span::styled(
+                    format!("{} ", commit.date),
+                    Style::default().fg(self.theme.muted),
+                ),
+                Span::styled(
+                    format!("{} ", commit.author),
+                    Style::default().fg(self.theme.secondary),
+                ),
+                Span::styled(commit.subject.clone(), Style::default().fg(self.theme.fg)),
+            ]));
+        }
+
+        if let (Some(base), Some(_)) = (&details.base, &details.merge_base) {
+            lines.push(Line::raw(""));
+            lines.push(self.heading(format!("Changes vs {}", base)));
+            if details.diff_stat.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No changes",
+                    Style::default().fg(self.theme.muted),
+                )));
+            }
+            for stat in &details.diff_stat {
+                lines.push(Line::from(Span::styled(
+                    stat.clone(),
+                    Style::default().fg(self.theme.fg),
+                )));
+            }
+        }
+
+        lines
+    }
+}
+
+impl Widget for BranchDetailsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.branch {
+            Some(branch) => format!(" {} · details ", branch),
+            None => " Select a branch ".to_string(),
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.muted))
+            .title(Span::styled(
+                title,
+                Style::default()
+                    .fg(self.theme.secondary)
+                    .add_modifier(Modifier::BOLD),
+            ));
+
+        let inner_area = block.inner(area);
+
+        let all_lines = match (self.branch, self.details) {
+            (None, _) => vec![Line::from(Span::styled(
+                "Select a branch to view its details",
+                Style::default().fg(self.theme.muted),
+            ))],
+            (Some(_), None) => vec![Line::from(Span::styled(
+                "Loading...",
+                Style::default().fg(self.theme.muted),
+            ))],
+            (Some(_), Some(Err(e))) => vec![Line::from(Span::styled(
+                format!("Error: {}", e),
+                Style::default().fg(self.theme.error),
+            ))],
+            (Some(_), Some(Ok(details))) => self.render_details(details),
+        };
+
+        // Update max scroll
+        let content_height = all_lines.len() as u16;
+        self.state.max_scroll = content_height.saturating_sub(inner_area.height);
+
+        // Apply scroll
+        let visible_lines: Vec<Line> = all_lines
+            .into_iter()
+            .skip(self.state.scroll as usize)
+            .take(inner_area.height as usize)
+            .collect();
+
+        let paragraph = Paragraph::new(visible_lines).block(block);
+        paragraph.render(area, buf);
+
+        // Render scrollbar if needed
+        if self.state.max_scroll > 0 {
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("↑"))
+                .end_symbol(Some("↓"));
+
+            let mut scrollbar_state = ScrollbarState::new(self.state.max_scroll as usize)
+                .position(self.state.scroll as usize);
+
+            StatefulWidget::render(
+                scrollbar,
+                area.inner(ratatui::layout::Margin {
+                    vertical: 1,
+                    horizontal: 0,
+                }),
+                buf,
+                &mut scrollbar_state,
+            );
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Fuzzy matching for list filters
+
+/// Match `pattern` as a case-insensitive subsequence of `text`
+///
+/// Returns a score (higher is better) and the char indices of `text` that
+/// matched. Matches at the start of a path segment or word (`feature/x`, `fix-y`)
+/// and consecutive matches score higher, gaps lower.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let text: Vec<char> = text.chars().collect();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut next = 0;
+
+    for p in pattern.chars().flat_map(char::to_lowercase) {
+        let offset = text[next..]
+            .iter()
+            .position(|c| c.to_lowercase().eq(std::iter::once(p)))?;
+        let pos = next + offset;
+
+        score += 1 - offset as i64;
+        if pos == 0 || matches!(text[pos - 1], '/' | '-' | '_' | '.') {
+            score += 16;
+        } else if offset == 0 && !positions.is_empty() {
+            score += 8;
+        }
+
+        positions.push(pos);
+        next = pos + 1;
+    }
+
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let (_, positions) = fuzzy_match("fx", "feature/x").unwrap();
+        assert_eq!(positions, vec![0, 8]);
+
+        assert!(fuzzy_match("FEAT", "feature/x").is_some());
+        assert!(fuzzy_match("xf", "feature/x").is_none());
+
+        // Segment starts beat matches in the middle of a word
+        let (boundary, _) = fuzzy_match("fy", "fix/yaml").unwrap();
+        let (inner, _) = fuzzy_match("fy", "fancy").unwrap();
+        assert!(boundary > inner);
+    }
+}
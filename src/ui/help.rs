@@ -37,7 +37,7 @@ impl Widget for HelpWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Center the help popup
         let popup_width = 50.min(area.width.saturating_sub(4));
-        let popup_height = 24.min(area.height.saturating_sub(4));
+        let popup_height = 38.min(area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2;
@@ -75,6 +75,11 @@ impl Widget for HelpWidget<'_> {
             self.render_keybinding("↑ / ↓", "Navigate branches"),
             self.render_keybinding("j / k", "Scroll command log"),
             self.render_keybinding("Mouse wheel", "Scroll command log"),
+            self.render_keybinding("/", "Filter branches (fuzzy)"),
+            self.render_keybinding("1 / 2 / 3", "Only local / remote / hooks"),
+            self.render_keybinding("S", "Cycle sort order"),
+            self.render_keybinding("T", "Toggle tree by prefix"),
+            self.render_keybinding("← / →", "Collapse / expand group"),
             Line::raw(""),
             Line::from(Span::styled(
                 " Actions",
@@ -87,6 +92,11 @@ impl Widget for HelpWidget<'_> {
             self.render_keybinding("o", "Exit & show cd command"),
             self.render_keybinding("d", "Delete worktree"),
             self.render_keybinding("u", "Untrack branch (hide)"),
+            self.render_keybinding("Space", "Mark branch (bulk actions)"),
+            self.render_keybinding("v", "Start / end marking a range"),
+            self.render_keybinding("Esc", "Clear marks"),
+            self.render_keybinding("x", "Cancel running hook"),
+            self.render_keybinding("z", "Trash (restore worktree)"),
             self.render_keybinding("r", "Refresh (fetch from remote)"),
             self.render_keybinding("a", "Toggle auto-create mode"),
             Line::raw(""),
@@ -96,8 +106,10 @@ impl Widget for HelpWidget<'_> {
                     .fg(self.theme.secondary)
                     .add_modifier(Modifier::BOLD),
             )),
+            self.render_keybinding("Tab", "Toggle log / branch details"),
             self.render_keybinding("l", "Full-screen logs"),
             self.render_keybinding("s", "Settings"),
+            self.render_keybinding("i", "Ignore list (patterns, hidden branches)"),
             self.render_keybinding("?", "Toggle this help"),
             self.render_keybinding("q / Esc", "Quit"),
             Line::raw(""),
@@ -110,7 +122,7 @@ impl Widget for HelpWidget<'_> {
             self.render_keybinding("Shift+drag", "Select text"),
             Line::raw(""),
             Line::from(Span::styled(
-                "Untracked branches can be restored in settings",
+                "Untracked branches can be restored in the ignore list (i)",
                 Style::default().fg(self.theme.muted),
             )),
         ];
@@ -1,11 +1,16 @@
 //! TUI components for git-worktree-agent
 
 mod branch_list;
+mod details;
+mod fuzzy;
 mod help;
 mod logs;
 mod status;
 
-pub use branch_list::{BranchItem, BranchListState, BranchListWidget, BranchStatus};
+pub use branch_list::{
+    BranchFilter, BranchItem, BranchListState, BranchListWidget, BranchStatus, StaleReason,
+};
+pub use details::BranchDetailsWidget;
 pub use help::HelpWidget;
 pub use logs::{BranchLogWidget, LogsState, ScrollableLogsWidget};
 pub use status::{AppStatus, StatusWidget};
@@ -26,6 +26,8 @@ pub struct AppStatus {
     pub running_hooks: usize,
     /// Last error message
     pub last_error: Option<String>,
+    /// Last notice about a remote branch change
+    pub last_notice: Option<String>,
     /// Auto-create enabled
     pub auto_create_enabled: bool,
     /// Poll interval in seconds
@@ -112,12 +114,22 @@ impl Widget for StatusWidget<'_> {
             Span::raw("")
         };
 
+        let notice_status = if let Some(ref notice) = self.status.last_notice {
+            Span::styled(
+                format!("│ {} ", truncate_str(notice, 40)),
+                Style::default().fg(self.theme.secondary),
+            )
+        } else {
+            Span::raw("")
+        };
+
         let line = Line::from(vec![
             fetch_status,
             auto_create,
             poll_info,
             branch_count,
             hook_status,
+            notice_status,
             error_status,
         ]);
 
@@ -140,6 +152,11 @@ fn truncate_str(s: &str, max_len: usize) -> &str {
     if s.len() <= max_len {
         s
     } else {
-        &s[..max_len.saturating_sub(3)]
+        let mut end = max_len.saturating_sub(3);
+        // Don't cut a multi-byte character in half
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        &s[..end]
     }
 }
@@ -2,58 +2,207 @@
 //!
 //! Polls the remote repository for new branches and triggers worktree creation
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
-use crate::config::Config;
+use crate::config::{
+    CleanupPolicy, CompiledPatterns, Config, HookStage, PollMode, RemoteConfig, UpdateAction,
+    Upstream,
+};
 use crate::executor::{CommandExecutor, CommandLog, CommandOutput, RunningCommand};
-use crate::git::{RemoteBranch, Repository, WorktreeAgent};
-use color_eyre::eyre::Result;
-use std::collections::HashMap;
+use crate::git::{
+    RemoteBranch, Repository, WorktreeAgent, WorktreeInfo, fetch_changed_refs, fetch_remote,
+    short_hash,
+};
+use crate::runtime::{HookRun, RuntimeState};
+use crate::trash::Trash;
+use crate::ui::{BranchStatus, StaleReason};
+use color_eyre::eyre::{Result, eyre};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::thread;
-use tracing::{debug, error};
+use tracing::{debug, error, info, warn};
 
 /// Events that can occur during watching
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
 pub enum WatcherEvent {
-    /// Fetch started
-    FetchStarted,
-    /// Fetch completed successfully (with optional output messages)
-    FetchCompleted(Option<String>),
-    /// Fetch failed
-    FetchFailed(String),
+    /// Fetch of a remote started
+    FetchStarted(String),
+    /// Fetch of a remote completed successfully (with optional output messages)
+    FetchCompleted(String, Option<String>),
+    /// Fetch of a remote failed
+    FetchFailed(String, String),
     /// New remote branches discovered
     NewBranchesFound(Vec<String>),
+    /// A known remote branch moved (new commits or a force-push), with the full
+    /// hashes of its old and new commit
+    BranchUpdated {
+        branch: String,
+        old: String,
+        new: String,
+    },
+    /// A known remote branch was deleted on its remote
+    BranchDeleted(String),
     /// Worktree creation started
     WorktreeCreating(String),
     /// Worktree created successfully
     WorktreeCreated(String, PathBuf),
-    /// Worktree creation failed
+    /// Worktree creation failed (or was cancelled by the pre-create hook)
     WorktreeCreateFailed(String, String),
+    /// Worktree removed
+    WorktreeRemoved(String, PathBuf),
+    /// Worktree removal failed (or was cancelled by the pre-remove hook)
+    WorktreeRemoveFailed(String, String),
     /// Hook started
-    HookStarted(String),
+    HookStarted(String, HookStage),
     /// Hook output received
-    #[allow(dead_code)]
     HookOutput(String, CommandOutput),
     /// Hook completed
-    HookCompleted(String, i32),
+    HookCompleted(String, HookStage, i32),
+}
+
+/// A hook command running for a branch
+struct RunningHook {
+    stage: HookStage,
+    command: RunningCommand,
+}
+
+/// Work held back until a pre-create/pre-remove hook succeeds
+enum DeferredAction {
+    /// Create the worktree (as a new branch from `base_ref` if set)
+    Create { base_ref: Option<String> },
+    /// Remove the worktree at this path
+    Remove {
+        path: PathBuf,
+        options: RemoveOptions,
+    },
+}
+
+/// How a worktree is removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemoveOptions {
+    /// Remove the worktree even with uncommitted changes
+    pub force: bool,
+    /// Delete the local branch afterwards
+    pub delete_branch: bool,
+}
+
+/// What a hook runs for, passed to the command as `GWA_*` environment variables
+pub struct HookContext<'a> {
+    /// Lifecycle stage (`GWA_STAGE`)
+    pub stage: HookStage,
+    /// Branch the hook runs for (`GWA_BRANCH`)
+    pub branch: &'a str,
+    /// Worktree path, even before creation or after removal (`GWA_WORKTREE_PATH`)
+    pub worktree_path: &'a Path,
+    /// Ref a new branch is created from; the configured base branch otherwise
+    pub base_ref: Option<&'a str>,
+    /// Remote branch the branch comes from (`GWA_REMOTE`)
+    pub upstream: &'a Upstream,
+}
+
+impl HookContext<'_> {
+    /// Directory the hook runs in
+    ///
+    /// Pre-create and post-remove hooks run in the main worktree since the branch's
+    /// worktree doesn't exist at that point.
+    pub fn working_dir(&self, repo: &Repository) -> PathBuf {
+        match self.stage {
+            HookStage::PreCreate | HookStage::PostRemove => repo.main_root().to_path_buf(),
+            _ => self.worktree_path.to_path_buf(),
+        }
+    }
+
+    /// Environment variables describing the hook's branch and repository
+    pub fn env(&self, repo: &Repository, config: &Config) -> Vec<(String, String)> {
+        let remote = self.upstream.remote.as_str();
+        let remote_ref = self.upstream.tracking_ref();
+        let local_ref = format!("refs/heads/{}", self.branch);
+
+        // The commit the hook is about: the new remote commit for post-update, the
+        // local branch otherwise (falling back to what a new worktree starts from)
+        let candidates = match self.stage {
+            HookStage::PostUpdate => [Some(remote_ref.as_str()), Some(local_ref.as_str()), None],
+            _ => [
+                Some(local_ref.as_str()),
+                Some(remote_ref.as_str()),
+                self.base_ref,
+            ],
+        };
+        let commit = candidates
+            .into_iter()
+            .flatten()
+            .find_map(|rev| repo.resolve_commit(rev))
+            .unwrap_or_default();
+
+        let base_branch = self
+            .base_ref
+            .or(config.base_branch_for(remote))
+            .unwrap_or_default();
+
+        [
+            ("GWA_STAGE", self.stage.name().to_string()),
+            ("GWA_BRANCH", self.branch.to_string()),
+            (
+                "GWA_WORKTREE_PATH",
+                self.worktree_path.display().to_string(),
+            ),
+            ("GWA_MAIN_ROOT", repo.main_root().display().to_string()),
+            ("GWA_REMOTE", remote.to_string()),
+            ("GWA_BASE_BRANCH", base_branch.to_string()),
+            ("GWA_COMMIT", commit),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+    }
+}
+
+/// A worktree whose remote branch was deleted
+struct OrphanedWorktree {
+    /// When the deletion was first noticed
+    since: DateTime<Utc>,
+    /// Why the cleanup policy keeps it, once it was checked (logged when it changes)
+    kept_because: Option<String>,
 }
 
 /// Background watcher state
 pub struct Watcher {
-    /// Known remote branches
+    /// Known branches of all watched remotes (plus local ones), by local name
     known_branches: HashMap<String, RemoteBranch>,
     /// Running hook commands
-    running_hooks: HashMap<String, RunningCommand>,
+    running_hooks: HashMap<String, RunningHook>,
+    /// Actions waiting for their pre-create/pre-remove hook to finish
+    deferred_actions: HashMap<String, DeferredAction>,
     /// Command logs
     pub command_logs: Vec<CommandLog>,
-    /// Is a fetch currently in progress?
-    fetch_in_progress: bool,
-    /// Queue of branches pending worktree creation (processed sequentially)
+    /// Remotes with a fetch in progress
+    fetching: HashSet<String>,
+    /// Queue of branches pending worktree creation
     pending_branches: Vec<String>,
-    /// Currently processing a worktree (branch name if any)
-    current_processing: Option<String>,
+    /// Branches currently being processed (creation plus post-create hook), at most
+    /// `max_concurrent_jobs` at a time
+    processing: Vec<String>,
+    /// Set while shutting down: queued branches stay queued but aren't started
+    queue_stopped: bool,
+    /// Base refs of queued (or processing) branches to create as new branches
+    new_branch_bases: HashMap<String, String>,
+    /// Worktrees left behind their moved remote branch by the fast-forward policy
+    stale_worktrees: HashMap<String, StaleReason>,
+    /// Worktrees whose remote branch was deleted, tracked by the cleanup policy
+    orphaned_worktrees: HashMap<String, OrphanedWorktree>,
+    /// Last time a remote was fetched
+    last_fetch: Option<DateTime<Utc>>,
+    /// Hook runs not written to the state file yet
+    unsaved_hook_runs: Vec<HookRun>,
+    /// Held while this instance owns the queue saved in the state file
+    queue_lock: Option<File>,
+    /// Remote branches left out because another remote's branch has their local
+    /// name, by full ref (reported once)
+    name_clashes: HashSet<String>,
 }
 
 impl Watcher {
@@ -62,19 +211,39 @@ impl Watcher {
         Self {
             known_branches: HashMap::new(),
             running_hooks: HashMap::new(),
+            deferred_actions: HashMap::new(),
             command_logs: Vec::new(),
-            fetch_in_progress: false,
+            fetching: HashSet::new(),
             pending_branches: Vec::new(),
-            current_processing: None,
+            processing: Vec::new(),
+            queue_stopped: false,
+            new_branch_bases: HashMap::new(),
+            stale_worktrees: HashMap::new(),
+            orphaned_worktrees: HashMap::new(),
+            last_fetch: None,
+            unsaved_hook_runs: Vec::new(),
+            queue_lock: None,
+            name_clashes: HashSet::new(),
         }
     }
 
     /// Initialize with current remote branches
     pub fn init(&mut self, repo: &Repository, config: &Config) -> Result<()> {
-        // Get remote branches
-        let remote_branches = repo.get_remote_branches(&config.remote_name)?;
-        for branch in remote_branches {
-            self.known_branches.insert(branch.name.clone(), branch);
+        self.known_branches.clear();
+
+        // Get remote branches; the first remote wins if local names clash
+        for remote in config.watched_remotes() {
+            for branch in Self::remote_branches(repo, config, &remote)? {
+                match self.known_branches.get(&branch.name) {
+                    Some(known) => {
+                        let taken_by = known.full_ref.clone();
+                        self.report_name_clash(&branch, &taken_by);
+                    }
+                    None => {
+                        self.known_branches.insert(branch.name.clone(), branch);
+                    }
+                }
+            }
         }
 
         // Get local branches
@@ -93,98 +262,281 @@ impl Watcher {
         Ok(())
     }
 
-    /// Check if fetch is currently in progress
+    /// Pick up where the last run left off (call after `init`)
+    ///
+    /// Remote branches that moved since they were last seen are reported as
+    /// updated by the next fetch, orphaned worktrees keep their grace period and
+    /// branches that were still queued are queued again unless they got a
+    /// worktree in the meantime. The queue is only taken over if no other running
+    /// instance owns it.
+    pub fn restore(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        state: RuntimeState,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        for (name, branch) in self.known_branches.iter_mut() {
+            let Some(commit) = state.branch_commits.get(name).filter(|_| !branch.is_local) else {
+                continue;
+            };
+            // Older state files hold abbreviated hashes, which didn't move if they
+            // abbreviate the current commit
+            if !branch.commit.starts_with(commit.as_str()) {
+                branch.commit = commit.clone();
+            }
+        }
+
+        self.last_fetch = state.last_fetch;
+        self.restore_orphans(&state);
+        self.queue_lock = RuntimeState::claim_queue(repo);
+        if self.queue_lock.is_none() {
+            info!("Another instance owns the worktree queue, not restoring it");
+            return;
+        }
+
+        let worktree_agent = WorktreeAgent::new(repo);
+        for branch in &state.pending_branches {
+            if !worktree_agent
+                .has_worktree_for_branch(branch)
+                .unwrap_or(false)
+                && !self.is_pending(branch)
+            {
+                self.pending_branches.push(branch.clone());
+                if let Some(base_ref) = state.new_branch_bases.get(branch) {
+                    self.new_branch_bases
+                        .insert(branch.clone(), base_ref.clone());
+                }
+            }
+        }
+
+        self.try_process_next(repo, config, event_tx);
+    }
+
+    /// Take over when orphaned worktrees were first seen from a saved state
+    ///
+    /// The next cleanup check forgets those that aren't orphaned anymore.
+    pub fn restore_orphans(&mut self, state: &RuntimeState) {
+        self.orphaned_worktrees = state
+            .orphaned_since
+            .iter()
+            .map(|(branch, since)| {
+                let orphan = OrphanedWorktree {
+                    since: *since,
+                    kept_because: None,
+                };
+                (branch.clone(), orphan)
+            })
+            .collect();
+    }
+
+    /// Save the state to pick up in the next run
+    ///
+    /// Merged into what other instances saved: hook runs are added, the branch
+    /// commits of the most recent fetch win, orphaned worktrees keep the earliest
+    /// time they were seen and the queue is only saved by its owner.
+    pub fn save_state(&mut self, repo: &Repository) {
+        let last_fetch = self.last_fetch;
+        let branch_commits = self
+            .known_branches
+            .iter()
+            .filter(|(_, branch)| !branch.is_local)
+            .map(|(name, branch)| (name.clone(), branch.commit.clone()))
+            .collect();
+        // Branches in the middle of creation are started over next time
+        let pending_branches: Option<Vec<String>> = self.queue_lock.is_some().then(|| {
+            self.processing
+                .iter()
+                .chain(&self.pending_branches)
+                .cloned()
+                .collect()
+        });
+        let new_branch_bases = self
+            .new_branch_bases
+            .iter()
+            .map(|(branch, base_ref)| (branch.clone(), base_ref.clone()))
+            .collect();
+        let orphaned_since: Vec<(String, DateTime<Utc>)> = self
+            .orphaned_worktrees
+            .iter()
+            .map(|(branch, orphan)| (branch.clone(), orphan.since))
+            .collect();
+        let hook_runs = std::mem::take(&mut self.unsaved_hook_runs);
+
+        let result = RuntimeState::update(repo, |state| {
+            state.orphaned_since = orphaned_since
+                .into_iter()
+                .map(|(branch, since)| {
+                    let since = state
+                        .orphaned_since
+                        .get(&branch)
+                        .map_or(since, |s| since.min(*s));
+                    (branch, since)
+                })
+                .collect();
+            if last_fetch >= state.last_fetch {
+                state.last_fetch = last_fetch;
+                state.branch_commits = branch_commits;
+            }
+            if let Some(pending_branches) = pending_branches {
+                state.pending_branches = pending_branches;
+                state.new_branch_bases = new_branch_bases;
+            }
+            for run in &hook_runs {
+                state.record_hook(run.clone());
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to save state: {}", e);
+            // Try again with the next save
+            self.unsaved_hook_runs.splice(0..0, hook_runs);
+        }
+    }
+
+    /// Last time a remote was fetched
+    pub fn last_fetch(&self) -> Option<DateTime<Utc>> {
+        self.last_fetch
+    }
+
+    /// Get the branches of a watched remote, named as they are checked out locally
+    ///
+    /// Branches matched by the remote's own ignore patterns are left out.
+    fn remote_branches(
+        repo: &Repository,
+        config: &Config,
+        remote: &RemoteConfig,
+    ) -> Result<Vec<RemoteBranch>> {
+        let ignored = CompiledPatterns::new(&remote.ignore_patterns);
+        let branches = repo
+            .get_remote_branches(&remote.name)?
+            .into_iter()
+            .filter(|branch| !ignored.matches(&branch.name))
+            .map(|branch| RemoteBranch {
+                name: config.local_branch_name(&remote.name, &branch.name),
+                ..branch
+            })
+            .collect();
+        Ok(branches)
+    }
+
+    /// Check if a fetch of any remote is currently in progress
     pub fn is_fetching(&self) -> bool {
-        self.fetch_in_progress
+        !self.fetching.is_empty()
     }
 
-    /// Start a background fetch (non-blocking)
+    /// Start a background fetch of each remote (non-blocking)
+    ///
+    /// Every remote is fetched on its own thread and reports its own
+    /// `FetchCompleted`/`FetchFailed`, so a slow or broken fork doesn't hold up the
+    /// others. Remotes that are still being fetched are skipped.
     pub fn start_fetch(
         &mut self,
         repo_root: PathBuf,
-        remote_name: String,
+        remotes: Vec<String>,
+        mode: PollMode,
         event_tx: mpsc::Sender<WatcherEvent>,
     ) {
-        if self.fetch_in_progress {
-            return;
-        }
-
-        self.fetch_in_progress = true;
-        let _ = event_tx.send(WatcherEvent::FetchStarted);
-
-        // Spawn background thread for fetch
-        thread::spawn(move || {
-            let result = std::process::Command::new("git")
-                .args(["fetch", "--prune", &remote_name])
-                .current_dir(&repo_root)
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped())
-                .output();
-
-            match result {
-                Ok(output) => {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-
-                    // Collect any output messages (warnings, info, etc.)
-                    let mut messages = Vec::new();
-                    for line in stderr.lines().chain(stdout.lines()) {
-                        let trimmed = line.trim();
-                        if !trimmed.is_empty() {
-                            messages.push(trimmed.to_string());
-                        }
-                    }
+        for remote_name in remotes {
+            if !self.fetching.insert(remote_name.clone()) {
+                continue;
+            }
 
-                    if output.status.success() {
-                        let output_msg = if messages.is_empty() {
-                            None
-                        } else {
-                            Some(messages.join("\n"))
-                        };
-                        let _ = event_tx.send(WatcherEvent::FetchCompleted(output_msg));
-                    } else {
-                        // Check if it's a real error or just a warning
-                        let is_real_error = stderr.lines().any(|line| {
-                            let line = line.trim().to_lowercase();
-                            !line.is_empty()
-                                && !line.starts_with("warning")
-                                && !line.contains("post-quantum")
-                                && !line.starts_with("hint:")
-                                && !line.starts_with("from ")
-                        });
+            let _ = event_tx.send(WatcherEvent::FetchStarted(remote_name.clone()));
+
+            // Spawn background thread for fetch
+            let repo_root = repo_root.clone();
+            let event_tx = event_tx.clone();
+            thread::spawn(move || {
+                let result = match mode {
+                    PollMode::Fetch => fetch_remote(&repo_root, &remote_name),
+                    PollMode::LsRemote => fetch_changed_refs(&repo_root, &remote_name),
+                };
+                let event = match result {
+                    Ok(output_msg) => WatcherEvent::FetchCompleted(remote_name, output_msg),
+                    Err(e) => WatcherEvent::FetchFailed(remote_name, e),
+                };
+                let _ = event_tx.send(event);
+            });
+        }
+    }
 
-                        if is_real_error {
-                            let _ = event_tx.send(WatcherEvent::FetchFailed(stderr.to_string()));
-                        } else {
-                            // It's just warnings, treat as success
-                            let output_msg = if messages.is_empty() {
-                                None
-                            } else {
-                                Some(messages.join("\n"))
-                            };
-                            let _ = event_tx.send(WatcherEvent::FetchCompleted(output_msg));
-                        }
-                    }
+    /// Advance the pipeline in response to an event
+    ///
+    /// Updates the known branches after a fetch and moves on to the next queued
+    /// branch once the current one is done, saving the runtime state as it goes.
+    /// Front-ends (TUI, daemon) call this for every event they receive before doing
+    /// their own bookkeeping.
+    pub fn handle_event(
+        &mut self,
+        event: &WatcherEvent,
+        repo: &Repository,
+        config: &Config,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        match event {
+            WatcherEvent::FetchCompleted(remote, output) => {
+                // Log fetch result to command logs
+                if let Some(msg) = output {
+                    self.add_fetch_log(remote, msg);
+                } else {
+                    // No warnings/errors - log simple success
+                    self.add_fetch_success_log(remote);
                 }
-                Err(e) => {
-                    let _ = event_tx.send(WatcherEvent::FetchFailed(e.to_string()));
+                // Process the completed fetch - update branches
+                self.on_fetch_complete(repo, config, remote, event_tx);
+                self.check_orphaned_worktrees(repo, config, event_tx);
+                Trash::new(repo).purge_expired(repo, config.trash_retention_secs);
+            }
+            WatcherEvent::FetchFailed(remote, msg) => {
+                self.on_fetch_failed(remote);
+                self.add_fetch_log(remote, &format!("Error: {}", msg));
+            }
+            // If no hook is configured, process next pending branch
+            WatcherEvent::WorktreeCreated(_, _)
+                if config.hook_command(HookStage::PostCreate).is_none() =>
+            {
+                self.try_process_next(repo, config, event_tx);
+            }
+            WatcherEvent::WorktreeCreateFailed(_, _) => {
+                // Process next pending branch (sequential worktree creation),
+                // even if this one failed
+                self.try_process_next(repo, config, event_tx);
+            }
+            WatcherEvent::HookCompleted(branch, _, exit_code) => {
+                // Carry on with whatever a pre-* hook was guarding
+                if let Some(action) = self.deferred_actions.remove(branch) {
+                    self.resume_deferred(repo, config, branch, action, *exit_code, event_tx);
                 }
+                self.try_process_next(repo, config, event_tx);
             }
-        });
+            _ => return,
+        }
+
+        self.save_state(repo);
     }
 
-    /// Called when fetch completes - update branch list
+    /// Called when the fetch of a remote completes - update branch list
     pub fn on_fetch_complete(
         &mut self,
         repo: &Repository,
-        config: &mut Config,
+        config: &Config,
+        remote_name: &str,
         event_tx: &mpsc::Sender<WatcherEvent>,
     ) {
-        self.fetch_in_progress = false;
-        config.last_fetch = Some(Utc::now());
+        self.fetching.remove(remote_name);
+        self.last_fetch = Some(Utc::now());
+
+        // The remote may have been dropped from the config while it was fetched
+        let Some(remote) = config
+            .watched_remotes()
+            .into_iter()
+            .find(|r| r.name == remote_name)
+        else {
+            return;
+        };
 
         // Get current remote branches
-        let remote_branches = match repo.get_remote_branches(&config.remote_name) {
+        let remote_branches = match Self::remote_branches(repo, config, &remote) {
             Ok(branches) => branches,
             Err(e) => {
                 error!("Failed to get remote branches: {}", e);
@@ -196,20 +548,81 @@ impl Watcher {
         let local_branches = repo.get_local_branches().unwrap_or_default();
 
         let mut new_branches = Vec::new();
+        let mut updated_branches = Vec::new();
+        let mut deleted_branches = Vec::new();
 
-        // Find new remote branches
+        // Find new remote branches and remote branches that moved
         for branch in &remote_branches {
-            if !self.known_branches.contains_key(&branch.name) {
-                // This is a new branch
-                if !config.should_ignore_branch(&branch.name) {
-                    new_branches.push(branch.name.clone());
+            match self.known_branches.get(&branch.name) {
+                // Same local name from another remote, which was there first
+                Some(known) if !known.is_local && known.remote != branch.remote => {
+                    let taken_by = known.full_ref.clone();
+                    self.report_name_clash(branch, &taken_by);
                 }
-                self.known_branches
-                    .insert(branch.name.clone(), branch.clone());
+                None => {
+                    // This is a new branch
+                    if !config.should_ignore(branch) {
+                        new_branches.push(branch.name.clone());
+                    }
+                    self.known_branches
+                        .insert(branch.name.clone(), branch.clone());
+                }
+                Some(known) if !known.is_local && known.commit != branch.commit => {
+                    let old = known.commit.clone();
+                    if !config.should_ignore(branch) {
+                        self.add_command_log(
+                            &branch.name,
+                            &format!(
+                                "{} updated: {} → {}",
+                                branch.full_ref,
+                                short_hash(&old),
+                                short_hash(&branch.commit)
+                            ),
+                        );
+                        let _ = event_tx.send(WatcherEvent::BranchUpdated {
+                            branch: branch.name.clone(),
+                            old,
+                            new: branch.commit.clone(),
+                        });
+                        updated_branches.push(branch.name.clone());
+                    }
+                    self.known_branches
+                        .insert(branch.name.clone(), branch.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        // Find branches of this remote that were deleted there
+        let remote_names: HashSet<&str> = remote_branches.iter().map(|b| b.name.as_str()).collect();
+        for (name, branch) in &self.known_branches {
+            if !branch.is_local
+                && branch.remote == remote_name
+                && !remote_names.contains(name.as_str())
+            {
+                deleted_branches.push(name.clone());
+            }
+        }
+
+        for name in &deleted_branches {
+            // A local branch of the same name stays known
+            let ignored = self
+                .known_branches
+                .remove(name)
+                .is_some_and(|branch| config.should_ignore(&branch));
+            self.stale_worktrees.remove(name);
+
+            if !ignored {
+                self.add_command_log(name, "Deleted on remote");
+                let _ = event_tx.send(WatcherEvent::BranchDeleted(name.clone()));
             }
         }
 
-        // Add local branches (only if not already tracked as remote)
+        // Add local branches (only if not already tracked as remote), drop
+        // local branches that no longer exist
+        let local_names: HashSet<&str> = local_branches.iter().map(|b| b.name.as_str()).collect();
+        self.known_branches
+            .retain(|name, branch| !branch.is_local || local_names.contains(name.as_str()));
         for branch in &local_branches {
             if !self.known_branches.contains_key(&branch.name) {
                 self.known_branches
@@ -217,27 +630,25 @@ impl Watcher {
             }
         }
 
-        // Build set of all current branch names (remote + local)
-        let mut current_names: std::collections::HashSet<String> =
-            remote_branches.iter().map(|b| b.name.clone()).collect();
-        for branch in &local_branches {
-            current_names.insert(branch.name.clone());
+        if !updated_branches.is_empty() {
+            self.on_branches_updated(repo, config, &updated_branches, event_tx);
         }
 
-        // Remove branches that no longer exist
-        self.known_branches
-            .retain(|name, _| current_names.contains(name));
-
         if !new_branches.is_empty() {
             let _ = event_tx.send(WatcherEvent::NewBranchesFound(new_branches.clone()));
 
-            // Auto-create worktrees if enabled - queue them for sequential processing
-            if config.auto_create_worktrees {
+            // Auto-create worktrees if enabled - queue them for processing.
+            // Only the instance owning the queue creates them; others just report.
+            if config.auto_create_worktrees && self.queue_lock.is_some() {
                 let worktree_agent = WorktreeAgent::new(repo);
 
                 for branch in &new_branches {
-                    // Skip if already tracked or untracked
-                    if config.should_ignore_branch(branch) {
+                    // Skip ignored branches, like the branch list does
+                    if self
+                        .known_branches
+                        .get(branch)
+                        .is_none_or(|known| config.should_ignore(known))
+                    {
                         continue;
                     }
 
@@ -255,49 +666,59 @@ impl Watcher {
                     }
                 }
 
-                // Start processing if not already doing so
-                if self.current_processing.is_none() && !self.pending_branches.is_empty() {
-                    self.process_next_branch(repo, config, event_tx);
-                }
+                // Start processing as many as the job limit allows
+                self.try_process_next(repo, config, event_tx);
             }
         }
     }
 
     /// Process the next branch in the queue (creates worktree + starts hook)
+    ///
+    /// `git worktree add` runs on the calling thread, so git operations stay
+    /// serialized; only the hooks of different branches overlap.
     fn process_next_branch(
         &mut self,
         repo: &Repository,
-        config: &mut Config,
+        config: &Config,
         event_tx: &mpsc::Sender<WatcherEvent>,
     ) {
         // Get the next branch from the queue
-        let Some(branch) = self.pending_branches.first().cloned() else {
-            self.current_processing = None;
+        if self.pending_branches.is_empty() {
             return;
-        };
+        }
 
-        self.pending_branches.remove(0);
-        self.current_processing = Some(branch.clone());
+        let branch = self.pending_branches.remove(0);
+        self.processing.push(branch.clone());
 
-        let worktree_agent = WorktreeAgent::new(repo);
-        let _ = self.create_worktree(repo, config, &branch, &worktree_agent, event_tx);
+        let base_ref = self.new_branch_bases.get(&branch).cloned();
+        self.start_create(repo, config, &branch, base_ref.as_deref(), event_tx);
     }
 
-    /// Check if we're currently processing branches or have pending ones
-    #[allow(dead_code)]
+    /// Mark a branch as done so the next queued branch can take its slot
+    fn finish_processing(&mut self, branch: &str) {
+        self.processing.retain(|b| b != branch);
+        self.new_branch_bases.remove(branch);
+    }
+
+    /// Check if a branch is being processed (queued ones don't count)
     pub fn is_processing(&self) -> bool {
-        self.current_processing.is_some() || !self.pending_branches.is_empty()
+        !self.processing.is_empty()
     }
 
     /// Get count of pending branches
     #[allow(dead_code)]
     pub fn pending_count(&self) -> usize {
-        self.pending_branches.len()
-            + if self.current_processing.is_some() {
-                1
-            } else {
-                0
-            }
+        self.pending_branches.len() + self.processing.len()
+    }
+
+    /// Stop starting queued branches, for shutting down
+    ///
+    /// Branches currently being processed are left alone. The queued ones stay in
+    /// the queue, so they are saved and queued again by the next run's `restore`;
+    /// they are returned.
+    pub fn stop_queue(&mut self) -> Vec<String> {
+        self.queue_stopped = true;
+        self.pending_branches.clone()
     }
 
     /// Check if a branch is in the pending queue
@@ -307,10 +728,7 @@ impl Watcher {
 
     /// Check if a branch is currently being processed
     pub fn is_current(&self, branch: &str) -> bool {
-        self.current_processing
-            .as_ref()
-            .map(|b| b == branch)
-            .unwrap_or(false)
+        self.processing.iter().any(|b| b == branch)
     }
 
     /// Check if a branch has a running hook
@@ -318,11 +736,74 @@ impl Watcher {
         self.running_hooks.contains_key(branch)
     }
 
+    /// Check if any hook is still running
+    pub fn has_running_hooks(&self) -> bool {
+        !self.running_hooks.is_empty()
+    }
+
+    /// Compute the display status of a branch from the queue and its worktree (if any)
+    pub fn branch_status(&self, branch: &str, worktree: Option<&WorktreeInfo>) -> BranchStatus {
+        // Check queue/processing status first
+        if self.is_current(branch) {
+            // Currently being processed - check if hook is running
+            if self.has_running_hook(branch) {
+                BranchStatus::RunningHook
+            } else {
+                BranchStatus::Creating
+            }
+        } else if self.is_pending(branch) {
+            BranchStatus::Queued
+        } else if let Some(wt) = worktree {
+            // Check if hook is running for this worktree
+            if self.has_running_hook(branch) {
+                BranchStatus::RunningHook
+            } else if wt.is_prunable {
+                BranchStatus::LocalPrunable
+            } else {
+                BranchStatus::LocalActive
+            }
+        } else {
+            BranchStatus::Remote
+        }
+    }
+
+    /// Cancel the running hook for a branch
+    ///
+    /// Returns false if no hook is running. The hook reports `HookCompleted` once
+    /// the process has been killed, which lets the queue move on.
+    pub fn cancel_hook(&mut self, branch: &str) -> bool {
+        match self.running_hooks.get(branch) {
+            Some(running) => {
+                running.command.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel every running hook and wait until their process groups are gone, for
+    /// exiting right away
+    ///
+    /// Returns the branches whose hooks were cancelled.
+    pub fn stop_hooks(&mut self) -> Vec<String> {
+        let hooks: Vec<(String, RunningHook)> = self.running_hooks.drain().collect();
+        for (_, running) in &hooks {
+            running.command.cancel();
+        }
+        hooks
+            .into_iter()
+            .map(|(branch, running)| {
+                running.command.wait();
+                branch
+            })
+            .collect()
+    }
+
     /// Queue a branch for worktree creation (used for manual creation)
     pub fn queue_branch(
         &mut self,
         repo: &Repository,
-        config: &mut Config,
+        config: &Config,
         branch: &str,
         event_tx: &mpsc::Sender<WatcherEvent>,
     ) {
@@ -334,15 +815,32 @@ impl Watcher {
         // Add to queue
         self.pending_branches.push(branch.to_string());
 
-        // Start processing if not already doing so
-        if self.current_processing.is_none() {
-            self.process_next_branch(repo, config, event_tx);
+        // Start processing if a job slot is free
+        self.try_process_next(repo, config, event_tx);
+        self.save_state(repo);
+    }
+
+    /// Queue a new branch, created from `base_ref` along with its worktree
+    pub fn queue_new_branch(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        branch: &str,
+        base_ref: &str,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        if self.is_pending(branch) || self.is_current(branch) {
+            return;
         }
+
+        self.new_branch_bases
+            .insert(branch.to_string(), base_ref.to_string());
+        self.queue_branch(repo, config, branch, event_tx);
     }
 
-    /// Called when fetch fails
-    pub fn on_fetch_failed(&mut self) {
-        self.fetch_in_progress = false;
+    /// Called when the fetch of a remote fails
+    pub fn on_fetch_failed(&mut self, remote_name: &str) {
+        self.fetching.remove(remote_name);
     }
 
     /// Check for running hooks output (call this frequently)
@@ -352,7 +850,7 @@ impl Watcher {
         for (branch, running) in &self.running_hooks {
             // Drain available output
             loop {
-                match running.output_rx.try_recv() {
+                match running.command.output_rx.try_recv() {
                     Ok(output) => {
                         // Find the log for this branch
                         if let Some(log) = self
@@ -364,73 +862,149 @@ impl Watcher {
                             log.add_output(output.clone());
                         }
 
-                        match &output {
-                            CommandOutput::Exit(code) => {
-                                let _ = event_tx
-                                    .send(WatcherEvent::HookCompleted(branch.clone(), *code));
-                                completed.push(branch.clone());
-                            }
-                            CommandOutput::Error(_) => {
-                                let _ =
-                                    event_tx.send(WatcherEvent::HookCompleted(branch.clone(), -1));
-                                completed.push(branch.clone());
-                            }
+                        let exit_code = match &output {
+                            CommandOutput::Exit(code) => *code,
+                            CommandOutput::Error(_) => -1,
                             _ => {
                                 let _ =
                                     event_tx.send(WatcherEvent::HookOutput(branch.clone(), output));
+                                continue;
                             }
-                        }
+                        };
+
+                        let _ = event_tx.send(WatcherEvent::HookCompleted(
+                            branch.clone(),
+                            running.stage,
+                            exit_code,
+                        ));
+                        completed.push((branch.clone(), running.stage, exit_code));
+                        break;
                     }
                     Err(mpsc::TryRecvError::Empty) => break,
                     Err(mpsc::TryRecvError::Disconnected) => {
-                        completed.push(branch.clone());
+                        // The command went away without reporting an exit code
+                        let _ = event_tx.send(WatcherEvent::HookCompleted(
+                            branch.clone(),
+                            running.stage,
+                            -1,
+                        ));
+                        completed.push((branch.clone(), running.stage, -1));
                         break;
                     }
                 }
             }
         }
 
-        // Remove completed hooks and clear current_processing if done
-        for branch in completed {
+        // Remove completed hooks and free the job slot if done
+        for (branch, stage, exit_code) in completed {
             self.running_hooks.remove(&branch);
-
-            // If this was the branch we were processing, mark as done
-            if self.current_processing.as_ref() == Some(&branch) {
-                self.current_processing = None;
+            self.unsaved_hook_runs.push(HookRun {
+                branch: branch.clone(),
+                stage,
+                exit_code,
+                finished_at: Utc::now(),
+            });
+
+            // A finished post-create hook is the last step for a queued branch
+            if stage == HookStage::PostCreate {
+                self.finish_processing(&branch);
             }
         }
     }
 
-    /// Try to process the next pending branch (call after hook completes)
+    /// Start pending branches while job slots are free (call after hook completes)
     pub fn try_process_next(
         &mut self,
         repo: &Repository,
-        config: &mut Config,
+        config: &Config,
         event_tx: &mpsc::Sender<WatcherEvent>,
     ) {
-        // Only process next if we're not currently processing anything
-        if self.current_processing.is_none() && !self.pending_branches.is_empty() {
+        while !self.queue_stopped
+            && self.processing.len() < config.max_concurrent_jobs.max(1)
+            && !self.pending_branches.is_empty()
+        {
             self.process_next_branch(repo, config, event_tx);
         }
     }
 
-    /// Create a worktree for a branch
-    pub fn create_worktree(
+    /// Create a worktree for a branch, running the pre-create hook first if configured
+    ///
+    /// With `base_ref` set, a new branch is created from it instead of checking out
+    /// an existing one. The result is reported as `WorktreeCreated` or
+    /// `WorktreeCreateFailed`.
+    fn start_create(
         &mut self,
         repo: &Repository,
-        config: &mut Config,
+        config: &Config,
         branch: &str,
-        worktree_agent: &WorktreeAgent,
+        base_ref: Option<&str>,
         event_tx: &mpsc::Sender<WatcherEvent>,
-    ) -> Result<()> {
-        let worktree_path = config.get_worktree_path(repo.root(), branch);
-
+    ) {
         let _ = event_tx.send(WatcherEvent::WorktreeCreating(branch.to_string()));
 
-        match worktree_agent.create(branch, &worktree_path, &config.remote_name) {
+        if let Some(busy) = self.hook_in_the_way(branch) {
+            self.fail_create(branch, &busy, event_tx);
+            return;
+        }
+
+        let worktree_path = config.get_worktree_path(repo.main_root(), branch);
+        let upstream = self.upstream(repo, config, branch);
+        let hook = HookContext {
+            stage: HookStage::PreCreate,
+            branch,
+            worktree_path: &worktree_path,
+            base_ref,
+            upstream: &upstream,
+        };
+
+        match self.run_hook(repo, config, &hook, event_tx) {
+            Ok(true) => {
+                self.deferred_actions.insert(
+                    branch.to_string(),
+                    DeferredAction::Create {
+                        base_ref: base_ref.map(str::to_string),
+                    },
+                );
+            }
+            Ok(false) => self.create_worktree(repo, config, branch, base_ref, event_tx),
+            // Nothing is deferred, so the creation is over
+            Err(e) => {
+                let message = format!("pre-create hook failed to start: {}", e);
+                self.fail_create(branch, &message, event_tx);
+            }
+        }
+    }
+
+    /// Create the worktree and start the post-create hook
+    fn create_worktree(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        branch: &str,
+        base_ref: Option<&str>,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        let worktree_agent = WorktreeAgent::new(repo);
+        let worktree_path = config.get_worktree_path(repo.main_root(), branch);
+
+        let result = match base_ref {
+            Some(base_ref) => worktree_agent.create_new_branch(branch, base_ref, &worktree_path),
+            None => worktree_agent.create(
+                branch,
+                &worktree_path,
+                &self.upstream(repo, config, branch).tracking_ref(),
+            ),
+        };
+
+        match result {
             Ok(log_messages) => {
                 // Log all the git output
-                self.add_worktree_log(branch, &log_messages);
+                self.add_worktree_log(branch, "git worktree add", &log_messages);
+
+                // A brand new branch isn't known from the last fetch yet
+                if base_ref.is_some() {
+                    self.add_local_branch(branch);
+                }
 
                 let _ = event_tx.send(WatcherEvent::WorktreeCreated(
                     branch.to_string(),
@@ -438,64 +1012,538 @@ impl Watcher {
                 ));
             }
             Err(e) => {
-                error!("Failed to create worktree for {}: {}", branch, e);
-                let _ = event_tx.send(WatcherEvent::WorktreeCreateFailed(
-                    branch.to_string(),
-                    e.to_string(),
-                ));
-                // Clear current_processing so next branch can proceed
-                self.current_processing = None;
-                return Ok(());
+                self.fail_create(branch, &e.to_string(), event_tx);
+                return;
             }
         }
 
         // Run post-create hook if configured
-        let mut hook_started = false;
-        if let Some(cmd) = config.post_create_command.clone() {
-            self.run_hook(branch, &cmd, &worktree_path, event_tx)?;
-            hook_started = true;
+        let upstream = self.upstream(repo, config, branch);
+        let hook = HookContext {
+            stage: HookStage::PostCreate,
+            branch,
+            worktree_path: &worktree_path,
+            base_ref,
+            upstream: &upstream,
+        };
+        let hook_started = self
+            .run_hook(repo, config, &hook, event_tx)
+            .unwrap_or_else(|e| {
+                error!("Failed to start post-create hook for {}: {}", branch, e);
+                false
+            });
+
+        // If no hook was started, free the job slot so the next branch can proceed
+        if !hook_started {
+            self.finish_processing(branch);
+            // Without a hook configured, `WorktreeCreated` moves the queue on;
+            // a hook that failed to start never sends the `HookCompleted` that would
+            if config.hook_command(HookStage::PostCreate).is_some() {
+                self.try_process_next(repo, config, event_tx);
+            }
         }
+    }
 
-        // If no hook was started, clear current_processing so next branch can proceed
-        if !hook_started {
-            self.current_processing = None;
+    /// Report a failed creation and let the queue move on
+    fn fail_create(&mut self, branch: &str, message: &str, event_tx: &mpsc::Sender<WatcherEvent>) {
+        error!("Failed to create worktree for {}: {}", branch, message);
+        self.add_command_log(branch, &format!("Failed to create worktree: {}", message));
+        let _ = event_tx.send(WatcherEvent::WorktreeCreateFailed(
+            branch.to_string(),
+            message.to_string(),
+        ));
+
+        // Free the job slot so the next branch can proceed
+        self.finish_processing(branch);
+    }
+
+    /// Remove a branch's worktree, running the pre-remove hook first if configured
+    ///
+    /// The result is reported as `WorktreeRemoved` or `WorktreeRemoveFailed`.
+    pub fn start_remove(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        branch: &str,
+        path: &Path,
+        options: RemoveOptions,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        if let Some(busy) = self.hook_in_the_way(branch) {
+            self.fail_remove(branch, &busy, event_tx);
+            return;
         }
 
-        Ok(())
+        let upstream = self.upstream(repo, config, branch);
+        let hook = HookContext {
+            stage: HookStage::PreRemove,
+            branch,
+            worktree_path: path,
+            base_ref: None,
+            upstream: &upstream,
+        };
+
+        match self.run_hook(repo, config, &hook, event_tx) {
+            Ok(true) => {
+                self.deferred_actions.insert(
+                    branch.to_string(),
+                    DeferredAction::Remove {
+                        path: path.to_path_buf(),
+                        options,
+                    },
+                );
+            }
+            Ok(false) => self.remove_worktree(repo, config, branch, path, options, event_tx),
+            // Nothing is deferred, so the removal is over
+            Err(e) => {
+                let message = format!("pre-remove hook failed to start: {}", e);
+                self.fail_remove(branch, &message, event_tx);
+            }
+        }
     }
 
-    /// Run a hook command for a branch
-    fn run_hook(
+    /// Report a removal that was not done
+    fn fail_remove(&mut self, branch: &str, message: &str, event_tx: &mpsc::Sender<WatcherEvent>) {
+        warn!("Not removing the worktree of {}: {}", branch, message);
+        self.add_command_log(branch, &format!("Not removing the worktree: {}", message));
+        let _ = event_tx.send(WatcherEvent::WorktreeRemoveFailed(
+            branch.to_string(),
+            message.to_string(),
+        ));
+    }
+
+    /// Remove the worktree and start the post-remove hook
+    fn remove_worktree(
         &mut self,
+        repo: &Repository,
+        config: &Config,
         branch: &str,
-        command: &str,
-        worktree_path: &Path,
+        path: &Path,
+        options: RemoveOptions,
         event_tx: &mpsc::Sender<WatcherEvent>,
-    ) -> Result<()> {
-        let _ = event_tx.send(WatcherEvent::HookStarted(branch.to_string()));
+    ) {
+        let worktree_agent = WorktreeAgent::new(repo);
+        // A worktree whose directory is already gone has nothing to keep
+        let result = if config.trash && path.exists() {
+            Trash::new(repo).put(repo, branch, path).map(|entry| {
+                self.add_command_log(
+                    branch,
+                    &format!(
+                        "Moved worktree at {} to the trash ({})",
+                        path.display(),
+                        entry.id
+                    ),
+                );
+            })
+        } else {
+            worktree_agent.remove(path, options.force)
+        };
+        if let Err(e) = result {
+            error!("Failed to remove worktree: {}", e);
+            let _ = event_tx.send(WatcherEvent::WorktreeRemoveFailed(
+                branch.to_string(),
+                e.to_string(),
+            ));
+            return;
+        }
 
-        // Create command log
-        let log = CommandLog::new(branch.to_string(), command.to_string());
-        self.command_logs.push(log);
+        self.stale_worktrees.remove(branch);
 
-        // Start async command in the worktree directory
-        let running = CommandExecutor::run_async(command.to_string(), worktree_path)?;
-        self.running_hooks.insert(branch.to_string(), running);
+        if options.delete_branch {
+            match worktree_agent.delete_branch(branch) {
+                Ok(()) => self.add_command_log(branch, &format!("Deleted branch {}", branch)),
+                Err(e) => {
+                    error!("Failed to delete branch {}: {}", branch, e);
+                    self.add_command_log(branch, &format!("Failed to delete branch: {}", e));
+                }
+            }
+        }
 
-        Ok(())
+        let _ = event_tx.send(WatcherEvent::WorktreeRemoved(
+            branch.to_string(),
+            path.to_path_buf(),
+        ));
+
+        let upstream = self.upstream(repo, config, branch);
+        let hook = HookContext {
+            stage: HookStage::PostRemove,
+            branch,
+            worktree_path: path,
+            base_ref: None,
+            upstream: &upstream,
+        };
+        if let Err(e) = self.run_hook(repo, config, &hook, event_tx) {
+            error!("Failed to start post-remove hook for {}: {}", branch, e);
+        }
     }
 
-    /// Start a hook for a manually created worktree (not through the normal queue)
-    pub fn start_hook(
+    /// Continue (or abandon) an action once its pre-* hook has finished
+    fn resume_deferred(
         &mut self,
-        branch: String,
-        command: String,
-        worktree_path: std::path::PathBuf,
-        event_tx: mpsc::Sender<WatcherEvent>,
+        repo: &Repository,
+        config: &Config,
+        branch: &str,
+        action: DeferredAction,
+        exit_code: i32,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        match action {
+            DeferredAction::Create { base_ref } => {
+                if exit_code == 0 {
+                    self.create_worktree(repo, config, branch, base_ref.as_deref(), event_tx);
+                } else {
+                    let message = format!("pre-create hook failed (exit code: {})", exit_code);
+                    self.fail_create(branch, &message, event_tx);
+                }
+            }
+            DeferredAction::Remove { path, options } => {
+                if exit_code == 0 {
+                    self.remove_worktree(repo, config, branch, &path, options, event_tx);
+                } else {
+                    let message = format!("pre-remove hook failed (exit code: {})", exit_code);
+                    self.fail_remove(branch, &message, event_tx);
+                }
+            }
+        }
+    }
+
+    /// Apply the configured update action to the worktrees of branches whose remote moved
+    fn on_branches_updated(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        branches: &[String],
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        match config.on_branch_update {
+            UpdateAction::Notify => {}
+            UpdateAction::FastForward => self.fast_forward_worktrees(repo, branches),
+            UpdateAction::Hook => self.run_update_hooks(repo, config, branches, event_tx),
+        }
+    }
+
+    /// Fast-forward the worktrees of branches whose remote moved
+    ///
+    /// Only clean worktrees that are strictly behind are touched; dirty and diverged
+    /// ones are skipped and flagged as stale until they catch up.
+    fn fast_forward_worktrees(&mut self, repo: &Repository, branches: &[String]) {
+        let worktree_agent = WorktreeAgent::new(repo);
+        let worktrees = worktree_agent.list().unwrap_or_default();
+
+        for branch in branches {
+            // Don't pull the rug from under a running hook
+            if self.has_running_hook(branch) {
+                continue;
+            }
+
+            let Some(worktree) = find_live_worktree(&worktrees, branch) else {
+                continue;
+            };
+
+            // The commit seen by this fetch, so a later push can't sneak in
+            let Some(upstream) = self.known_branches.get(branch).map(|b| b.commit.clone()) else {
+                continue;
+            };
+
+            let reason = match worktree_agent.is_dirty(&worktree.path) {
+                Ok(true) => Some(StaleReason::Dirty),
+                Ok(false) => match worktree_agent.ahead_behind(&worktree.path, &upstream) {
+                    // Strictly behind
+                    Ok((0, behind)) if behind > 0 => None,
+                    // Already contains the new commit (pulled by hand or pushed from here)
+                    Ok((_, 0)) => {
+                        self.stale_worktrees.remove(branch);
+                        continue;
+                    }
+                    Ok(_) => Some(StaleReason::Diverged),
+                    Err(e) => {
+                        error!("Failed to compare {} with {}: {}", branch, upstream, e);
+                        continue;
+                    }
+                },
+                Err(e) => {
+                    error!("Failed to check worktree of {}: {}", branch, e);
+                    continue;
+                }
+            };
+
+            if let Some(reason) = reason {
+                warn!(
+                    "Not fast-forwarding {}: worktree is {}",
+                    branch,
+                    reason.label()
+                );
+                self.add_command_log(
+                    branch,
+                    &format!(
+                        "Skipped fast-forward to {}: worktree is {}",
+                        upstream,
+                        reason.label()
+                    ),
+                );
+                self.stale_worktrees.insert(branch.clone(), reason);
+                continue;
+            }
+
+            match worktree_agent.fast_forward(&worktree.path, &upstream) {
+                Ok(messages) => {
+                    self.stale_worktrees.remove(branch);
+                    self.add_worktree_log(branch, "git merge --ff-only", &messages);
+                }
+                Err(e) => {
+                    error!("Failed to fast-forward {}: {}", branch, e);
+                    self.add_command_log(branch, &format!("Fast-forward failed: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Apply the cleanup policy to worktrees whose remote branch was deleted
+    ///
+    /// Deleted branches are found through git's `[gone]` upstream tracking, so
+    /// worktrees orphaned while gwa wasn't running are picked up too (their grace
+    /// period starts when they are first seen).
+    fn check_orphaned_worktrees(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) {
+        if config.cleanup == CleanupPolicy::Off {
+            self.orphaned_worktrees.clear();
+            return;
+        }
+
+        let worktrees = WorktreeAgent::new(repo).list().unwrap_or_default();
+        let remote_prefixes: Vec<String> = config
+            .remote_names()
+            .iter()
+            .map(|r| format!("refs/remotes/{}/", r))
+            .collect();
+
+        // Only branches that track a watched remote and still have a worktree
+        let orphaned: Vec<&WorktreeInfo> = repo
+            .get_gone_branches()
+            .unwrap_or_default()
+            .iter()
+            .filter(|(_, upstream)| remote_prefixes.iter().any(|p| upstream.starts_with(p)))
+            .filter_map(|(branch, _)| find_live_worktree(&worktrees, branch))
+            .filter(|worktree| !worktree.is_main)
+            .collect();
+
+        // Forget branches that were pushed again or whose worktree is gone
+        self.orphaned_worktrees.retain(|branch, _| {
+            orphaned
+                .iter()
+                .any(|w| w.branch.as_deref() == Some(branch.as_str()))
+        });
+
+        let now = Utc::now();
+        let grace_period = chrono::Duration::seconds(
+            i64::try_from(config.cleanup_grace_period_secs).unwrap_or(i64::MAX),
+        );
+
+        for worktree in orphaned {
+            let Some(branch) = worktree.branch.as_deref() else {
+                continue;
+            };
+
+            if !self.orphaned_worktrees.contains_key(branch) {
+                info!("Remote branch of {} was deleted", branch);
+                self.add_command_log(branch, "Remote branch deleted, worktree is orphaned");
+                self.orphaned_worktrees.insert(
+                    branch.to_string(),
+                    OrphanedWorktree {
+                        since: now,
+                        kept_because: None,
+                    },
+                );
+            }
+
+            if config.cleanup != CleanupPolicy::RemoveIfCleanAndMerged
+                || now - self.orphaned_worktrees[branch].since < grace_period
+                || self.is_busy(branch)
+            {
+                continue;
+            }
+
+            match self.cleanup_blocker(repo, config, worktree) {
+                Some(reason) => {
+                    let kept_because = &mut self
+                        .orphaned_worktrees
+                        .get_mut(branch)
+                        .unwrap()
+                        .kept_because;
+                    if kept_because.as_ref() != Some(&reason) {
+                        *kept_because = Some(reason.clone());
+                        info!("Keeping orphaned worktree of {}: {}", branch, reason);
+                        self.add_command_log(branch, &format!("Kept worktree: {}", reason));
+                    }
+                }
+                None => {
+                    info!(
+                        "Removing orphaned worktree of {} at {}",
+                        branch,
+                        worktree.path.display()
+                    );
+                    self.add_command_log(
+                        branch,
+                        &format!(
+                            "Removing worktree at {}: remote branch deleted, clean and merged",
+                            worktree.path.display()
+                        ),
+                    );
+                    self.start_remove(
+                        repo,
+                        config,
+                        branch,
+                        &worktree.path,
+                        RemoveOptions::default(),
+                        event_tx,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Why an orphaned worktree must be kept, if it must
+    ///
+    /// A worktree is only removed without uncommitted changes and with its HEAD
+    /// merged into the base branch, which also rules out unpushed commits.
+    fn cleanup_blocker(
+        &self,
+        repo: &Repository,
+        config: &Config,
+        worktree: &WorktreeInfo,
+    ) -> Option<String> {
+        match WorktreeAgent::new(repo).is_dirty(&worktree.path) {
+            Ok(false) => {}
+            Ok(true) => return Some("uncommitted changes".to_string()),
+            Err(e) => return Some(format!("could not check for changes: {}", e)),
+        }
+
+        let branch = worktree.branch.as_deref().unwrap_or_default();
+        let upstream = self.upstream(repo, config, branch);
+        let Some(base_ref) = base_ref(repo, config, &upstream) else {
+            return Some("no base branch to check the merge against".to_string());
+        };
+
+        if !repo.is_merged(&worktree.head, &base_ref) {
+            let base = base_ref.strip_prefix("refs/remotes/").unwrap_or(&base_ref);
+            return Some(format!("not merged into {}", base));
+        }
+
+        None
+    }
+
+    /// Check whether a branch has a job queued, running or waiting on a hook
+    fn is_busy(&self, branch: &str) -> bool {
+        self.is_current(branch)
+            || self.is_pending(branch)
+            || self.has_running_hook(branch)
+            || self.deferred_actions.contains_key(branch)
+    }
+
+    /// When the remote branch of an orphaned worktree was found deleted, if it was
+    pub fn orphaned_since(&self, branch: &str) -> Option<DateTime<Utc>> {
+        self.orphaned_worktrees.get(branch).map(|o| o.since)
+    }
+
+    /// Why a worktree was left behind its remote branch, if it was
+    pub fn stale_reason(&self, branch: &str) -> Option<StaleReason> {
+        self.stale_worktrees.get(branch).copied()
+    }
+
+    /// Run the post-update hook in the worktrees of branches whose remote moved
+    fn run_update_hooks(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        branches: &[String],
+        event_tx: &mpsc::Sender<WatcherEvent>,
     ) {
-        if let Err(e) = self.run_hook(&branch, &command, &worktree_path, &event_tx) {
-            tracing::error!("Failed to start hook for {}: {}", branch, e);
+        if config.hook_command(HookStage::PostUpdate).is_none() {
+            return;
+        }
+
+        let worktrees = WorktreeAgent::new(repo).list().unwrap_or_default();
+
+        for branch in branches {
+            // Don't interrupt a hook that is still running for this branch
+            if self.has_running_hook(branch) {
+                continue;
+            }
+
+            let Some(worktree) = find_live_worktree(&worktrees, branch) else {
+                continue;
+            };
+
+            let upstream = self.upstream(repo, config, branch);
+            let hook = HookContext {
+                stage: HookStage::PostUpdate,
+                branch,
+                worktree_path: &worktree.path,
+                base_ref: None,
+                upstream: &upstream,
+            };
+            if let Err(e) = self.run_hook(repo, config, &hook, event_tx) {
+                error!("Failed to start post-update hook for {}: {}", branch, e);
+            }
+        }
+    }
+
+    /// Start the hook configured for a stage, returning false if there is none
+    fn run_hook(
+        &mut self,
+        repo: &Repository,
+        config: &Config,
+        hook: &HookContext,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) -> Result<bool> {
+        let Some(command) = config.hook_command(hook.stage) else {
+            return Ok(false);
+        };
+        // Its handle would be lost, so it could neither be cancelled nor reaped
+        if let Some(busy) = self.hook_in_the_way(hook.branch) {
+            return Err(eyre!("{}", busy));
         }
+
+        let branch = hook.branch.to_string();
+        let mut log = CommandLog::new(branch.clone(), format!("{}: {}", hook.stage, command));
+
+        // Start async command; one that failed to start is logged, but never started
+        let running = match CommandExecutor::run_async(
+            command.to_string(),
+            &hook.working_dir(repo),
+            hook.env(repo, config),
+            config.hook_timeout(),
+        ) {
+            Ok(running) => running,
+            Err(e) => {
+                log.add_output(CommandOutput::Error(format!("{:#}", e)));
+                self.command_logs.push(log);
+                return Err(e);
+            }
+        };
+
+        let _ = event_tx.send(WatcherEvent::HookStarted(branch.clone(), hook.stage));
+        self.command_logs.push(log);
+        self.running_hooks.insert(
+            branch,
+            RunningHook {
+                stage: hook.stage,
+                command: running,
+            },
+        );
+
+        Ok(true)
+    }
+
+    /// Why a branch can't start another hook yet: one hook at a time per branch
+    fn hook_in_the_way(&self, branch: &str) -> Option<String> {
+        self.running_hooks
+            .get(branch)
+            .map(|running| format!("its {} hook is still running", running.stage))
     }
 
     /// Get all known branches (both local and remote)
@@ -508,6 +1556,22 @@ impl Watcher {
         self.known_branches.get(name)
     }
 
+    /// The remote branch a local branch comes from (see [`Config::upstream`])
+    ///
+    /// Branches without a known remote branch (e.g., deleted on the remote) fall
+    /// back to what they track, if that is on a watched remote.
+    pub fn upstream(&self, repo: &Repository, config: &Config, name: &str) -> Upstream {
+        if let Some(branch) = self.known_branches.get(name).filter(|b| !b.is_local) {
+            return config.upstream(branch);
+        }
+        match repo.get_tracked_branch(name) {
+            Some((remote, branch)) if config.remote_names().contains(&remote) => {
+                Upstream { remote, branch }
+            }
+            _ => config.default_upstream(name),
+        }
+    }
+
     /// Add a new local branch to the known branches list
     pub fn add_local_branch(&mut self, name: &str) {
         if !self.known_branches.contains_key(name) {
@@ -518,6 +1582,9 @@ impl Watcher {
                     name: name.to_string(),
                     remote: String::new(),
                     commit: String::new(), // We don't have the commit hash readily available
+                    commit_time: 0,
+                    author: String::new(),
+                    author_email: String::new(),
                     is_local: true,
                 },
             );
@@ -552,9 +1619,9 @@ impl Watcher {
         self.command_logs.push(log);
     }
 
-    /// Add a log entry for worktree creation
-    pub fn add_worktree_log(&mut self, branch: &str, messages: &[String]) {
-        let mut log = CommandLog::new(branch.to_string(), format!("git worktree add ({})", branch));
+    /// Add a log entry for a git command run in or for a worktree
+    pub fn add_worktree_log(&mut self, branch: &str, command: &str, messages: &[String]) {
+        let mut log = CommandLog::new(branch.to_string(), format!("{} ({})", command, branch));
 
         for line in messages {
             if line.starts_with("ERROR:") {
@@ -568,6 +1635,20 @@ impl Watcher {
         self.command_logs.push(log);
     }
 
+    /// Report a remote branch that can't be watched because its local name is
+    /// taken by a branch of another remote (once per branch)
+    fn report_name_clash(&mut self, branch: &RemoteBranch, taken_by: &str) {
+        if !self.name_clashes.insert(branch.full_ref.clone()) {
+            return;
+        }
+        let message = format!(
+            "{} is not watched: its local name {} is taken by {}",
+            branch.full_ref, branch.name, taken_by
+        );
+        warn!("{}", message);
+        self.add_command_log(&branch.name, &message);
+    }
+
     /// Add a single log message for a branch (just the command header, no output)
     pub fn add_command_log(&mut self, branch: &str, message: &str) {
         let log = CommandLog::new(branch.to_string(), message.to_string());
@@ -575,8 +1656,717 @@ impl Watcher {
     }
 }
 
+/// The remote-tracking ref of the base branch a branch is compared against
+/// (e.g., "refs/remotes/origin/main")
+///
+/// Uses the base branch configured for the branch's remote, or the remote's
+/// default branch.
+pub fn base_ref(repo: &Repository, config: &Config, upstream: &Upstream) -> Option<String> {
+    let remote = upstream.remote.as_str();
+    config
+        .base_branch_for(remote)
+        .map(str::to_string)
+        .or_else(|| repo.get_default_branch(remote))
+        .map(|base| format!("refs/remotes/{}/{}", remote, base))
+}
+
+/// Find the worktree of a branch, unless its directory is gone
+fn find_live_worktree<'a>(worktrees: &'a [WorktreeInfo], branch: &str) -> Option<&'a WorktreeInfo> {
+    worktrees
+        .iter()
+        .find(|w| w.branch.as_deref() == Some(branch) && !w.is_prunable)
+}
+
 impl Default for Watcher {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use std::time::Duration;
+
+    /// A repository at `<dir>/main` with a bare remote that has `main` and the
+    /// given branches, all fetched
+    pub(crate) fn setup(name: &str, branches: &[&str]) -> (PathBuf, Repository) {
+        let dir = std::env::temp_dir().join(format!("gwa-watcher-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let main = dir.join("main");
+        fs::create_dir_all(&main).unwrap();
+
+        git(&dir, &["init", "-q", "--bare", "-b", "main", "remote.git"]);
+        git(&main, &["init", "-q", "-b", "main"]);
+        git(&main, &["remote", "add", "origin", "../remote.git"]);
+        commit(&main, "init");
+        git(&main, &["push", "-q", "origin", "main"]);
+        for branch in branches {
+            git(
+                &main,
+                &["push", "-q", "origin", &format!("main:{}", branch)],
+            );
+        }
+        git(&main, &["fetch", "-q", "origin"]);
+        git(&main, &["branch", "-q", "--set-upstream-to=origin/main"]);
+
+        (dir, Repository::discover(&main).unwrap())
+    }
+
+    /// Run git in `dir` and return its trimmed stdout
+    pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    pub(crate) fn commit(dir: &Path, message: &str) {
+        git(
+            dir,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@example.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+        );
+    }
+
+    /// Start a watcher the way the TUI and the daemon do
+    pub(crate) fn start(
+        repo: &Repository,
+        config: &Config,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+    ) -> Watcher {
+        let mut watcher = Watcher::new();
+        watcher.init(repo, config).unwrap();
+        watcher.restore(repo, config, RuntimeState::load(repo), event_tx);
+        watcher
+    }
+
+    /// Add a commit to a branch of the remote, as if pushed from another clone
+    fn push(dir: &Path, branch: &str) -> String {
+        let remote = dir.join("remote.git");
+        let tree = format!("{}^{{tree}}", branch);
+        let commit = git(
+            &remote,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@example.com",
+                "commit-tree",
+                &tree,
+                "-p",
+                branch,
+                "-m",
+                "update",
+            ],
+        );
+        git(
+            &remote,
+            &["update-ref", &format!("refs/heads/{}", branch), &commit],
+        );
+        commit
+    }
+
+    /// Poll the remotes once and handle everything that came of it
+    fn poll(
+        watcher: &mut Watcher,
+        repo: &Repository,
+        config: &Config,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+        event_rx: &mpsc::Receiver<WatcherEvent>,
+    ) -> Vec<WatcherEvent> {
+        watcher.start_fetch(
+            repo.root().to_path_buf(),
+            config.remote_names(),
+            config.poll_mode,
+            event_tx.clone(),
+        );
+        let mut events = Vec::new();
+        while watcher.is_fetching() {
+            let event = event_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+            watcher.handle_event(&event, repo, config, event_tx);
+            events.push(event);
+        }
+        events.extend(handle_events(watcher, repo, config, event_tx, event_rx));
+        events
+    }
+
+    /// Handle the events sent so far, like the event loops do
+    fn handle_events(
+        watcher: &mut Watcher,
+        repo: &Repository,
+        config: &Config,
+        event_tx: &mpsc::Sender<WatcherEvent>,
+        event_rx: &mpsc::Receiver<WatcherEvent>,
+    ) -> Vec<WatcherEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            watcher.handle_event(&event, repo, config, event_tx);
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_hook_env() {
+        let (dir, repo) = setup("hook-env", &["feature/x"]);
+        let mut config = Config::default();
+        config.base_branch = Some("main".to_string());
+        let upstream = config.default_upstream("feature/x");
+        let path = config.get_worktree_path(repo.main_root(), "feature/x");
+        let env = |hook: &HookContext| -> HashMap<String, String> {
+            hook.env(&repo, &config).into_iter().collect()
+        };
+
+        // Before creation the commit is the remote branch's
+        let env_pre_create = env(&HookContext {
+            stage: HookStage::PreCreate,
+            branch: "feature/x",
+            worktree_path: &path,
+            base_ref: None,
+            upstream: &upstream,
+        });
+        assert_eq!(env_pre_create["GWA_STAGE"], "pre-create");
+        assert_eq!(env_pre_create["GWA_BRANCH"], "feature/x");
+        assert_eq!(
+            env_pre_create["GWA_WORKTREE_PATH"],
+            path.display().to_string()
+        );
+        assert_eq!(
+            env_pre_create["GWA_MAIN_ROOT"],
+            repo.main_root().display().to_string()
+        );
+        assert_eq!(env_pre_create["GWA_REMOTE"], "origin");
+        assert_eq!(env_pre_create["GWA_BASE_BRANCH"], "main");
+        assert_eq!(
+            env_pre_create["GWA_COMMIT"],
+            git(repo.root(), &["rev-parse", "origin/feature/x"])
+        );
+
+        // A new branch that is gone again falls back to the ref it was based on
+        let upstream = config.default_upstream("feature/new");
+        let env_post_remove = env(&HookContext {
+            stage: HookStage::PostRemove,
+            branch: "feature/new",
+            worktree_path: &path,
+            base_ref: Some("refs/remotes/origin/main"),
+            upstream: &upstream,
+        });
+        assert_eq!(env_post_remove["GWA_STAGE"], "post-remove");
+        assert_eq!(env_post_remove["GWA_BRANCH"], "feature/new");
+        assert_eq!(env_post_remove["GWA_REMOTE"], "origin");
+        assert_eq!(
+            env_post_remove["GWA_BASE_BRANCH"],
+            "refs/remotes/origin/main"
+        );
+        assert_eq!(
+            env_post_remove["GWA_COMMIT"],
+            git(repo.root(), &["rev-parse", "origin/main"])
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stopped_queue_is_saved() {
+        let (dir, repo) = setup("queue", &["feature/x", "feature/y"]);
+        let mut config = Config::default();
+        config.hooks.post_create = Some("true".to_string());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        // One job at a time: feature/y waits for the hook of feature/x
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        watcher.queue_branch(&repo, &config, "feature/y", &event_tx);
+        assert!(watcher.is_current("feature/x"));
+        assert_eq!(watcher.stop_queue(), ["feature/y"]);
+
+        while watcher.is_processing() {
+            watcher.check_running_hooks(&event_tx);
+            handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(watcher.is_pending("feature/y"));
+        watcher.save_state(&repo);
+        drop(watcher);
+        assert_eq!(RuntimeState::load(&repo).pending_branches, ["feature/y"]);
+
+        // The next run picks it up
+        let _watcher = start(&repo, &config, &event_tx);
+        let worktree_agent = WorktreeAgent::new(&repo);
+        assert!(worktree_agent.has_worktree_for_branch("feature/y").unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_queue_moves_on_when_hook_fails_to_start() {
+        let (dir, repo) = setup("hook-spawn", &["feature/x", "feature/y"]);
+        let mut config = Config::default();
+        // sh can't be given an argument with a NUL byte
+        config.hooks.post_create = Some("true\0".to_string());
+        RuntimeState::update(&repo, |state| {
+            state.pending_branches = vec!["feature/x".to_string(), "feature/y".to_string()];
+        })
+        .unwrap();
+
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+
+        let worktree_agent = WorktreeAgent::new(&repo);
+        assert!(worktree_agent.has_worktree_for_branch("feature/x").unwrap());
+        assert!(worktree_agent.has_worktree_for_branch("feature/y").unwrap());
+        assert!(!watcher.is_processing() && !watcher.has_running_hooks());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_hook_that_fails_to_start() {
+        let (dir, repo) = setup("hook-error", &["feature/x"]);
+        let mut config = Config::default();
+        config.hooks.post_create = Some("true\0".to_string());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        let events = handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, WatcherEvent::HookStarted(..)))
+        );
+
+        // Its log shows why, instead of a hook that runs forever
+        let log = watcher.command_logs.last().unwrap();
+        assert_eq!(log.command, "post-create: true\0");
+        assert!(!log.is_running);
+        assert!(matches!(log.output.as_slice(), [CommandOutput::Error(_)]));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pre_hooks_that_fail_to_start() {
+        let (dir, repo) = setup("pre-hook-error", &["feature/x", "feature/y"]);
+        let mut config = Config::default();
+        config.hooks.pre_create = Some("true\0".to_string());
+        config.hooks.pre_remove = Some("true\0".to_string());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        // The creation is given up and the queue moves on
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        watcher.queue_branch(&repo, &config, "feature/y", &event_tx);
+        let events = handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        for branch in ["feature/x", "feature/y"] {
+            assert!(events.iter().any(
+                |event| matches!(event, WatcherEvent::WorktreeCreateFailed(b, _) if b == branch)
+            ));
+            assert!(!watcher.is_busy(branch));
+        }
+
+        // So is a removal, which leaves the worktree alone
+        config.hooks.pre_create = None;
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let path = config.get_worktree_path(repo.main_root(), "feature/x");
+        let options = RemoveOptions::default();
+        watcher.start_remove(&repo, &config, "feature/x", &path, options, &event_tx);
+        let events = handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(matches!(
+            events.as_slice(),
+            [WatcherEvent::WorktreeRemoveFailed(branch, _)] if branch == "feature/x"
+        ));
+        assert!(!watcher.is_busy("feature/x"));
+        assert!(path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_one_hook_per_branch() {
+        let (dir, repo) = setup("busy", &["feature/x"]);
+        let mut config = Config::default();
+        config.hooks.post_create = Some("sleep 30".to_string());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        assert!(watcher.has_running_hook("feature/x"));
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+
+        // Removing now would take the post-create hook's place
+        let path = config.get_worktree_path(repo.main_root(), "feature/x");
+        let options = RemoveOptions::default();
+        watcher.start_remove(&repo, &config, "feature/x", &path, options, &event_tx);
+        let events = handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(matches!(
+            events.as_slice(),
+            [WatcherEvent::WorktreeRemoveFailed(branch, _)] if branch == "feature/x"
+        ));
+        assert!(path.exists());
+
+        // The hook can still be cancelled, which frees the job slot
+        assert!(watcher.cancel_hook("feature/x"));
+        while watcher.has_running_hooks() {
+            watcher.check_running_hooks(&event_tx);
+            thread::sleep(Duration::from_millis(10));
+        }
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(!watcher.is_processing());
+
+        watcher.start_remove(&repo, &config, "feature/x", &path, options, &event_tx);
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_new_branch_waits_for_a_job_slot() {
+        let (dir, repo) = setup("new-branch", &["feature/x"]);
+        let mut config = Config::default();
+        config.hooks.post_create = Some("sleep 30".to_string());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        watcher.queue_branch(&repo, &config, "feature/x", &event_tx);
+        watcher.queue_new_branch(
+            &repo,
+            &config,
+            "feature/new",
+            "refs/remotes/origin/main",
+            &event_tx,
+        );
+        assert!(watcher.is_pending("feature/new"));
+        assert_eq!(
+            RuntimeState::load(&repo).new_branch_bases["feature/new"],
+            "refs/remotes/origin/main"
+        );
+
+        // Its turn comes once the post-create hook of feature/x is done
+        assert!(watcher.cancel_hook("feature/x"));
+        while !watcher.is_current("feature/new") {
+            watcher.check_running_hooks(&event_tx);
+            handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+            thread::sleep(Duration::from_millis(10));
+        }
+        let path = config.get_worktree_path(repo.main_root(), "feature/new");
+        assert_eq!(
+            git(&path, &["rev-parse", "HEAD"]),
+            git(&dir.join("main"), &["rev-parse", "origin/main"])
+        );
+
+        watcher.stop_hooks();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_only_queue_owner_auto_creates() {
+        let (dir, repo) = setup("queue-owner", &[]);
+        let mut config = Config::default();
+        config.auto_create_worktrees = true;
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut owner = start(&repo, &config, &event_tx);
+        let mut other = start(&repo, &config, &event_tx);
+        git(&dir.join("remote.git"), &["branch", "feature/new", "main"]);
+
+        // The other instance sees the branch but leaves it to the owner
+        let events = poll(&mut other, &repo, &config, &event_tx, &event_rx);
+        assert!(events.iter().any(
+            |event| matches!(event, WatcherEvent::NewBranchesFound(branches) if branches == &["feature/new"])
+        ));
+        assert!(!other.is_pending("feature/new") && !other.is_processing());
+        let worktree_agent = WorktreeAgent::new(&repo);
+        assert!(
+            !worktree_agent
+                .has_worktree_for_branch("feature/new")
+                .unwrap()
+        );
+
+        poll(&mut owner, &repo, &config, &event_tx, &event_rx);
+        assert!(
+            worktree_agent
+                .has_worktree_for_branch("feature/new")
+                .unwrap()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ls_remote_fetches_changed_refs() {
+        let (dir, repo) = setup("ls-remote", &["feature/x", "fix/y"]);
+        let main = dir.join("main");
+        let mut config = Config::default();
+        config.poll_mode = PollMode::LsRemote;
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        let moved = push(&dir, "feature/x");
+        git(&dir.join("remote.git"), &["branch", "-q", "-D", "fix/y"]);
+        let events = poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let output = events.iter().find_map(|event| match event {
+            WatcherEvent::FetchCompleted(remote, output) if remote == "origin" => output.clone(),
+            _ => None,
+        });
+        assert!(output.unwrap().contains("- [deleted] origin/fix/y"));
+        assert_eq!(git(&main, &["rev-parse", "origin/feature/x"]), moved);
+        assert_eq!(
+            git(&main, &["for-each-ref", "refs/remotes/origin/fix/y"]),
+            ""
+        );
+        assert!(watcher.get_branch_by_name("fix/y").is_none());
+
+        // Nothing changed since: nothing is fetched
+        let events = poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(matches!(
+            events.as_slice(),
+            [
+                WatcherEvent::FetchStarted(_),
+                WatcherEvent::FetchCompleted(_, None)
+            ]
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fast_forward_clean_worktrees() {
+        let branches = ["feature/x", "feature/dirty", "feature/ahead"];
+        let (dir, repo) = setup("fast-forward", &branches);
+        let mut config = Config::default();
+        config.on_branch_update = UpdateAction::FastForward;
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        for branch in branches {
+            watcher.queue_branch(&repo, &config, branch, &event_tx);
+        }
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let path = |branch| config.get_worktree_path(repo.main_root(), branch);
+        fs::write(path("feature/dirty").join("notes.txt"), "wip").unwrap();
+        commit(&path("feature/ahead"), "local");
+        let dirty_head = git(&path("feature/dirty"), &["rev-parse", "HEAD"]);
+
+        let moved = push(&dir, "feature/x");
+        for branch in &branches[1..] {
+            push(&dir, branch);
+        }
+        poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+
+        assert_eq!(git(&path("feature/x"), &["rev-parse", "HEAD"]), moved);
+        assert_eq!(watcher.stale_reason("feature/x"), None);
+        assert_eq!(
+            git(&path("feature/dirty"), &["rev-parse", "HEAD"]),
+            dirty_head
+        );
+        assert_eq!(
+            watcher.stale_reason("feature/dirty"),
+            Some(StaleReason::Dirty)
+        );
+        assert_eq!(
+            watcher.stale_reason("feature/ahead"),
+            Some(StaleReason::Diverged)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_branch_updated_and_deleted_events() {
+        let (dir, repo) = setup("branch-events", &["feature/x", "fix/y", "tmp/a", "tmp/b"]);
+        let main = dir.join("main");
+        let mut config = Config::default();
+        config.ignore_patterns = vec!["tmp/*".to_string()];
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+        let old = git(&main, &["rev-parse", "origin/feature/x"]);
+
+        // Ignored branches move and go away silently
+        push(&dir, "feature/x");
+        push(&dir, "tmp/a");
+        git(
+            &dir.join("remote.git"),
+            &["branch", "-q", "-D", "fix/y", "tmp/b"],
+        );
+        let events = poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let new = git(&main, &["rev-parse", "origin/feature/x"]);
+
+        let branch_events: Vec<_> = events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    WatcherEvent::BranchUpdated { .. } | WatcherEvent::BranchDeleted(_)
+                )
+            })
+            .collect();
+        assert!(matches!(
+            branch_events.as_slice(),
+            [
+                WatcherEvent::BranchUpdated { branch, old: from, new: to },
+                WatcherEvent::BranchDeleted(deleted),
+            ] if branch == "feature/x" && *from == old && *to == new && deleted == "fix/y"
+        ));
+        assert_eq!(watcher.get_branch_by_name("feature/x").unwrap().commit, new);
+        assert!(watcher.get_branch_by_name("fix/y").is_none());
+        assert!(watcher.get_branch_by_name("tmp/b").is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_abbreviated_commits_in_state() {
+        let (dir, repo) = setup("short-hashes", &["feature/x"]);
+        let config = Config::default();
+        let main = dir.join("main");
+        let commit = git(&main, &["rev-parse", "origin/feature/x"]);
+
+        // State saved by a gwa that stored abbreviated hashes
+        RuntimeState::update(&repo, |state| {
+            state
+                .branch_commits
+                .insert("feature/x".to_string(), commit[..7].to_string());
+        })
+        .unwrap();
+
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+        let events = poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, WatcherEvent::BranchUpdated { .. }))
+        );
+
+        // Full hashes are what is kept from now on
+        assert_eq!(
+            watcher.get_branch_by_name("feature/x").unwrap().commit,
+            commit
+        );
+        assert_eq!(
+            RuntimeState::load(&repo).branch_commits["feature/x"],
+            commit
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_orphan_cleanup_policy() {
+        let branches = ["feature/merged", "feature/dirty", "feature/unmerged"];
+        let (dir, repo) = setup("cleanup", &branches);
+        let mut config = Config::default();
+        config.cleanup = CleanupPolicy::RemoveIfCleanAndMerged;
+        config.cleanup_grace_period_secs = 3600;
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        for branch in branches {
+            watcher.queue_branch(&repo, &config, branch, &event_tx);
+        }
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let paths = branches.map(|branch| config.get_worktree_path(repo.main_root(), branch));
+        let [merged, dirty, unmerged] = &paths;
+        fs::write(dirty.join("notes.txt"), "wip").unwrap();
+        commit(unmerged, "local");
+
+        let mut args = vec!["branch", "-q", "-D"];
+        args.extend(branches);
+        git(&dir.join("remote.git"), &args);
+
+        // Within the grace period the worktrees are only marked
+        poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        for (branch, path) in branches.iter().zip(&paths) {
+            assert!(watcher.orphaned_since(branch).is_some(), "{}", branch);
+            assert!(path.exists(), "{}", branch);
+        }
+
+        // Then only the clean and merged one goes
+        config.cleanup_grace_period_secs = 0;
+        let events = poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            WatcherEvent::WorktreeRemoved(branch, _) if branch == "feature/merged"
+        )));
+        assert!(!merged.exists());
+        assert!(dirty.exists());
+        assert!(unmerged.exists());
+        let kept = |branch| &watcher.orphaned_worktrees[branch].kept_because;
+        assert_eq!(
+            kept("feature/dirty").as_deref(),
+            Some("uncommitted changes")
+        );
+        assert_eq!(
+            kept("feature/unmerged").as_deref(),
+            Some("not merged into origin/main")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_orphan_grace_period_survives_restart() {
+        let (dir, repo) = setup("cleanup-restart", &["feature/gone"]);
+        let mut config = Config::default();
+        config.cleanup = CleanupPolicy::RemoveIfCleanAndMerged;
+        config.cleanup_grace_period_secs = 3600;
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = start(&repo, &config, &event_tx);
+
+        watcher.queue_branch(&repo, &config, "feature/gone", &event_tx);
+        handle_events(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let path = config.get_worktree_path(repo.main_root(), "feature/gone");
+        git(
+            &dir.join("remote.git"),
+            &["branch", "-q", "-D", "feature/gone"],
+        );
+
+        poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        let since = watcher.orphaned_since("feature/gone").unwrap();
+        drop(watcher);
+
+        // A restart keeps the time the orphan was first seen
+        let mut watcher = start(&repo, &config, &event_tx);
+        assert_eq!(watcher.orphaned_since("feature/gone"), Some(since));
+        poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert_eq!(watcher.orphaned_since("feature/gone"), Some(since));
+        assert!(path.exists());
+        drop(watcher);
+
+        // So the grace period runs out across restarts
+        RuntimeState::update(&repo, |state| {
+            state.orphaned_since.insert(
+                "feature/gone".to_string(),
+                since - chrono::Duration::hours(2),
+            );
+        })
+        .unwrap();
+        let mut watcher = start(&repo, &config, &event_tx);
+        poll(&mut watcher, &repo, &config, &event_tx, &event_rx);
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}